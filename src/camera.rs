@@ -1,4 +1,5 @@
 use crate::math::*;
+use rand::Rng;
 
 /// A thin-lens camera model with configurable field of view, aspect ratio,
 /// focus distance, and aperture size. The camera constructs an orthonormal
@@ -16,12 +17,17 @@ pub struct Camera {
     vertical: Vec3,
     u: Vec3,
     v: Vec3,
+    w: Vec3,
     lens_radius: f64,
+    focus_dist: f64,
+    shutter_open: f64,
+    shutter_close: f64,
 }
 
 /// Configuration builder for the camera, following the builder pattern
 /// to allow incremental, readable camera setup.
 /// Configuration for the thin-lens camera model with depth-of-field.
+#[derive(Clone, Copy)]
 pub struct CameraConfig {
     pub look_from: Point3,
     pub look_at: Point3,
@@ -46,6 +52,39 @@ impl Default for CameraConfig {
     }
 }
 
+impl CameraConfig {
+    /// Turns the camera in place by Euler angles in degrees: `yaw` about the
+    /// up vector (positive turns left), then `pitch` about the camera's right
+    /// axis (positive looks up), then `roll` about the view direction. The
+    /// distance to `look_at` is preserved. The up vector pitches along with
+    /// the view, so looking straight up or down keeps a valid basis.
+    pub fn rotate(&mut self, yaw: f64, pitch: f64, roll: f64) {
+        let up = self.vup.normalized();
+        let forward = (self.look_at - self.look_from).rotate_about(up, yaw.to_radians());
+        let right = forward.cross(up);
+        let forward = forward.rotate_about(right, pitch.to_radians());
+        let up = up.rotate_about(right, pitch.to_radians());
+        self.vup = up.rotate_about(forward, -roll.to_radians());
+        self.look_at = self.look_from + forward;
+    }
+
+    /// The same camera moved `degrees` around `look_at`, about the up
+    /// vector — one step of a turntable orbit.
+    pub fn orbited(mut self, degrees: f64) -> Self {
+        let offset = self.look_from - self.look_at;
+        self.look_from = self.look_at + offset.rotate_about(self.vup, degrees.to_radians());
+        self
+    }
+
+    /// The same camera moved `distance` along its right axis, still aimed at
+    /// `look_at` — one eye of a toed-in stereo pair.
+    pub fn shifted_right(mut self, distance: f64) -> Self {
+        let right = self.vup.cross(self.look_from - self.look_at).normalized();
+        self.look_from += right * distance;
+        self
+    }
+}
+
 impl Camera {
     /// Constructs the camera from configuration. The orthonormal basis is:
     ///   w = normalize(look_from - look_at)   (points backward, away from scene)
@@ -73,18 +112,79 @@ impl Camera {
             vertical,
             u,
             v,
+            w,
             lens_radius: config.aperture / 2.0,
+            focus_dist: config.focus_dist,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
         }
     }
 
+    /// Opens the shutter over `[open, close]`; primary rays are then spread
+    /// uniformly across that interval for motion blur.
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Self {
+        self.shutter_open = open;
+        self.shutter_close = close;
+        self
+    }
+
+    /// Signed distance of `point` from the focus plane along the viewing
+    /// axis, as a fraction of the focus distance: zero is perfectly in
+    /// focus, positive lies beyond the plane.
+    pub fn focus_offset(&self, point: Point3) -> f64 {
+        ((self.origin - point).dot(self.w) - self.focus_dist) / self.focus_dist
+    }
+
+    /// The ray through (s, t) from the center of the lens at shutter open:
+    /// the line of sight of that film position, without depth-of-field blur.
+    pub fn center_ray(&self, s: f64, t: f64) -> Ray {
+        Ray::with_time(
+            self.origin,
+            self.lower_left + self.horizontal * s + self.vertical * t - self.origin,
+            self.shutter_open,
+        )
+    }
+
     /// Generates a primary ray for the given (s, t) coordinates in [0,1]².
     /// When `lens_radius > 0`, the ray origin is perturbed for depth-of-field.
+    /// With an open shutter the ray's time is drawn uniformly across it.
     pub fn get_ray(&self, s: f64, t: f64, rng: &mut dyn rand::RngCore) -> Ray {
         let rd = Vec3::random_in_unit_disk(rng) * self.lens_radius;
         let offset = self.u * rd.x + self.v * rd.y;
-        Ray::new(
+        let time = if self.shutter_close > self.shutter_open {
+            self.shutter_open + (self.shutter_close - self.shutter_open) * rng.gen::<f64>()
+        } else {
+            self.shutter_open
+        };
+        Ray::with_time(
             self.origin + offset,
             self.lower_left + self.horizontal * s + self.vertical * t - self.origin - offset,
+            time,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_pitches_straight_up_without_degenerating() {
+        let mut config = CameraConfig {
+            look_from: Point3::zero(),
+            look_at: Point3::new(0.0, 0.0, -1.0),
+            aspect_ratio: 1.0,
+            focus_dist: 1.0,
+            ..CameraConfig::default()
+        };
+        config.rotate(0.0, 90.0, 0.0);
+        let camera = Camera::new(&config);
+        let center = camera.center_ray(0.5, 0.5).direction.normalized();
+        assert!((center - Vec3::unit_y()).length() < 1e-9, "{center:?}");
+        // Tipping the head back turns the top of the frame away from the old
+        // view direction.
+        let top = camera.center_ray(0.5, 1.0).direction;
+        assert!(top.z > 0.0, "{top:?}");
+        assert!(top.x.abs() < 1e-9, "{top:?}");
+    }
+}
@@ -0,0 +1,195 @@
+use crate::math::*;
+use std::f64::consts::PI;
+
+// ─── Spherical Harmonics ────────────────────────────────────────────────────
+
+/// Real spherical-harmonic basis for bands 0–2 at unit direction `d`.
+fn sh_basis(d: Vec3) -> [f64; 9] {
+    [
+        0.282_095,
+        0.488_603 * d.y,
+        0.488_603 * d.z,
+        0.488_603 * d.x,
+        1.092_548 * d.x * d.y,
+        1.092_548 * d.y * d.z,
+        0.315_392 * (3.0 * d.z * d.z - 1.0),
+        1.092_548 * d.x * d.z,
+        0.546_274 * (d.x * d.x - d.y * d.y),
+    ]
+}
+
+/// Per-coefficient scale that turns projected radiance into irradiance: the
+/// clamped-cosine kernel in SH, π, 2π/3, π/4 for bands 0, 1, 2
+/// (Ramamoorthi & Hanrahan 2001).
+const COSINE_LOBE: [f64; 9] = [
+    PI,
+    2.0 * PI / 3.0,
+    2.0 * PI / 3.0,
+    2.0 * PI / 3.0,
+    PI / 4.0,
+    PI / 4.0,
+    PI / 4.0,
+    PI / 4.0,
+    PI / 4.0,
+];
+
+/// Incident radiance around a point, projected onto nine SH coefficients
+/// per color channel. Band 2 is enough to reproduce irradiance to within a
+/// few percent, since the cosine kernel filters out higher frequencies.
+#[derive(Debug, Clone, Copy)]
+pub struct ShRadiance {
+    coeffs: [Color; 9],
+}
+
+impl ShRadiance {
+    pub fn zero() -> Self {
+        Self {
+            coeffs: [Color::zero(); 9],
+        }
+    }
+
+    /// Adds one Monte Carlo sample of radiance arriving from unit
+    /// `direction`; `weight` is 1 / (pdf · sample count).
+    pub fn add_sample(&mut self, direction: Vec3, radiance: Color, weight: f64) {
+        for (c, y) in self.coeffs.iter_mut().zip(sh_basis(direction)) {
+            *c += radiance * (y * weight);
+        }
+    }
+
+    /// Adds `other` scaled by `w`, for blending neighbouring records.
+    fn add_scaled(&mut self, other: &ShRadiance, w: f64) {
+        for (c, o) in self.coeffs.iter_mut().zip(other.coeffs) {
+            *c += o * w;
+        }
+    }
+
+    /// Irradiance onto a surface with unit `normal`.
+    pub fn irradiance(&self, normal: Vec3) -> Color {
+        let mut e = Color::zero();
+        for ((c, y), k) in self.coeffs.iter().zip(sh_basis(normal)).zip(COSINE_LOBE) {
+            e += *c * (y * k);
+        }
+        Color::new(e.x.max(0.0), e.y.max(0.0), e.z.max(0.0))
+    }
+}
+
+// ─── Irradiance Cache ───────────────────────────────────────────────────────
+
+/// Cells across the largest extent of the cached points. A lookup blends
+/// the records within one cell size.
+const CACHE_RESOLUTION: f64 = 16.0;
+/// Records whose normal deviates more than this cosine from the lookup
+/// normal are ignored, so light does not bleed around corners.
+const MIN_NORMAL_COS: f64 = 0.8;
+
+struct Record {
+    point: Point3,
+    normal: Vec3,
+    radiance: ShRadiance,
+}
+
+/// A sparse set of surface points with baked SH radiance, bucketed in a
+/// uniform grid over their bounds. Interpolating it replaces the tail of
+/// diffuse paths in the `--sh-cache` preview: fast and smooth, but biased.
+pub struct IrradianceCache {
+    records: Vec<Record>,
+    origin: Point3,
+    cell: f64,
+    dims: [usize; 3],
+    /// Record indices per grid cell, x fastest.
+    cells: Vec<Vec<usize>>,
+}
+
+impl IrradianceCache {
+    /// Builds the cache from `(point, unit normal, radiance)` records.
+    pub fn new(records: Vec<(Point3, Vec3, ShRadiance)>) -> Self {
+        // Size the grid from the central 80% of the points on each axis, so
+        // a few distant hits (e.g. far along a ground plane) do not stretch
+        // the cells. Records off the grid are dropped; lookups there miss.
+        let axis = |f: fn(&Point3) -> f64| {
+            let mut v: Vec<f64> = records.iter().map(|(p, _, _)| f(p)).collect();
+            v.sort_by(|a, b| a.total_cmp(b));
+            v.get(v.len() / 10)
+                .zip(v.get(v.len() * 9 / 10))
+                .map_or((0.0, 0.0), |(lo, hi)| (*lo, *hi))
+        };
+        let [(x0, x1), (y0, y1), (z0, z1)] = [axis(|p| p.x), axis(|p| p.y), axis(|p| p.z)];
+        let extent = (x1 - x0).max(y1 - y0).max(z1 - z0);
+        let cell = (extent / CACHE_RESOLUTION).max(1e-3);
+        // One cell of margin on every side.
+        let origin = Point3::new(x0 - cell, y0 - cell, z0 - cell);
+        let dims = [x1 - x0, y1 - y0, z1 - z0].map(|s| (s / cell) as usize + 3);
+
+        let mut cache = Self {
+            records: Vec::with_capacity(records.len()),
+            origin,
+            cell,
+            dims,
+            cells: vec![Vec::new(); dims[0] * dims[1] * dims[2]],
+        };
+        for (i, (point, normal, radiance)) in records.into_iter().enumerate() {
+            if let Some([x, y, z]) = cache.cell_of(point) {
+                let index = cache.index(x, y, z);
+                cache.cells[index].push(i);
+            }
+            cache.records.push(Record {
+                point,
+                normal,
+                radiance,
+            });
+        }
+        cache
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Grid coordinates of `p`, unclamped.
+    fn coords(&self, p: Point3) -> [i64; 3] {
+        let d = (p - self.origin) / self.cell;
+        [d.x, d.y, d.z].map(|c| c.floor() as i64)
+    }
+
+    /// Grid cell containing `p`, if it lies on the grid.
+    fn cell_of(&self, p: Point3) -> Option<[usize; 3]> {
+        let c = self.coords(p);
+        let on_grid = (0..3).all(|i| c[i] >= 0 && (c[i] as usize) < self.dims[i]);
+        on_grid.then(|| c.map(|c| c as usize))
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        (z * self.dims[1] + y) * self.dims[0] + x
+    }
+
+    /// Irradiance at `point` with unit `normal`, blended from records within
+    /// one cell size whose normals agree. `None` where the cache has no
+    /// coverage.
+    pub fn lookup(&self, point: Point3, normal: Vec3) -> Option<Color> {
+        let range = |c: i64, dim: usize| (c - 1).max(0)..=(c + 1).min(dim as i64 - 1);
+        // An empty range when the point is off the grid.
+        let [cx, cy, cz] = self.coords(point);
+        // SH projection is linear, so the records' coefficients are blended
+        // first and the irradiance evaluated once.
+        let mut blend = ShRadiance::zero();
+        let mut total = 0.0;
+        for z in range(cz, self.dims[2]) {
+            for y in range(cy, self.dims[1]) {
+                for x in range(cx, self.dims[0]) {
+                    let cell = &self.cells[self.index(x as usize, y as usize, z as usize)];
+                    for record in cell.iter().map(|&i| &self.records[i]) {
+                        let distance = (record.point - point).length() / self.cell;
+                        let facing = record.normal.dot(normal);
+                        if distance >= 1.0 || facing < MIN_NORMAL_COS {
+                            continue;
+                        }
+                        let w = (1.0 - distance) * facing;
+                        blend.add_scaled(&record.radiance, w);
+                        total += w;
+                    }
+                }
+            }
+        }
+        (total > 0.0).then(|| blend.irradiance(normal) / total)
+    }
+}
@@ -7,7 +7,8 @@
 //!
 //! The renderer implements a standard unidirectional path tracer with:
 //! - **Geometric primitives**: Sphere, Plane, Triangle, Quad, Disk with BVH acceleration
-//! - **Materials**: Lambertian, Metal, Dielectric (glass), Emissive, Checkerboard, Gradient
+//! - **Materials**: Lambertian, Metal, Dielectric (glass), Emissive, Checkerboard, Gradient,
+//!   ThinTranslucent (paper/leaf)
 //! - **Camera**: Thin-lens model with configurable DoF (depth of field)
 //! - **Output modes**: Braille (2×4 subpixel), TrueColor, HalfBlock, ASCII
 //! - **Tone mapping**: None, Reinhard, ACES filmic
@@ -25,14 +26,28 @@
 //! integrator recursively traces the scattered ray to evaluate `L_i`.
 
 mod camera;
+mod irradiance;
 mod math;
+mod pdf;
 mod presets;
 mod renderer;
+mod sampler;
 mod scene;
 
+use camera::{Camera, CameraConfig};
 use clap::Parser;
+use math::{Color, Ray, Vec3};
 use presets::ScenePreset;
-use renderer::{display_framebuffer, OutputMode, PathTracer, RenderConfig, ToneMapOp};
+use renderer::{
+    display_framebuffer, film_uv, Framebuffer, Guide, LiveDisplay, OutputMode, PassPreview,
+    PathTracer, PhysicalSky, RenderConfig, RenderStats, ResampleFilter, SkyModel, Stereo,
+    ToneMapOp, DEFAULT_RAY_EPSILON,
+};
+use sampler::SamplerKind;
+use scene::{extent_scale, BvhNode, Hittable, LightHandle, SkySun};
+
+/// Pass limit for `--converge` when `--passes` is not given.
+const DEFAULT_MAX_PASSES: u32 = 256;
 
 /// photon-cli — render 3D scenes in your terminal
 #[derive(Parser, Debug)]
@@ -65,31 +80,333 @@ struct Cli {
     height: u32,
 
     /// Samples per pixel — higher values reduce noise at the cost of render time.
-    /// 10–50 for previews, 200+ for high quality.
-    #[arg(long, default_value_t = 32)]
-    spp: u32,
+    /// 10–50 for previews, 200+ for high quality. Defaults to the scene's
+    /// recommendation
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    spp: Option<u32>,
 
     /// Maximum ray bounce depth. Higher values are needed for glass and
-    /// complex interreflections. 8–16 is typically sufficient.
-    #[arg(long, default_value_t = 12)]
-    bounces: u32,
+    /// complex interreflections. 8–16 is typically sufficient. Defaults to
+    /// the scene's recommendation
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    bounces: Option<u32>,
+
+    /// Source of the per-sample random numbers: independent pseudorandom
+    /// numbers, or a Halton/Sobol sequence randomized per pixel, which
+    /// converges faster and looks smoother at low --spp
+    #[arg(long, value_enum, default_value_t = CliSampler::Random)]
+    sampler: CliSampler,
+
+    /// Fast preview: trace one pixel per N×N block and upscale
+    /// (nearest-neighbor) to fill the output. Drop the flag for the final
+    /// full-resolution render
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    preview_downscale: u32,
+
+    /// Most primitives per BVH leaf. Larger leaves mean a shallower tree and
+    /// fewer box tests but more primitive tests; the image is unchanged
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    bvh_leaf_size: u32,
+
+    /// Print the scene's composition (primitive and material counts,
+    /// emitters, surface area and bounds) before rendering
+    #[arg(long)]
+    print_scene_stats: bool,
+
+    /// Worker threads: a number ≥ 1, or `auto` to use every available core.
+    /// `1` renders serially, which is handy for debugging
+    #[arg(long, default_value = "auto", value_parser = parse_threads)]
+    threads: usize,
+
+    /// Cap throughput at this many million samples per second, sleeping
+    /// between rows to keep a laptop cool and quiet on long renders
+    #[arg(long, value_parser = parse_positive)]
+    throttle: Option<f64>,
+
+    /// Render the scene N times (reusing the BVH) and report min/median/mean
+    /// timings. Only the last image is displayed or saved
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    repeat_render: u32,
+
+    /// Progressive refinement: render up to N passes of --spp samples each,
+    /// averaging them and reporting how much each pass changed the image
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..),
+          conflicts_with_all = ["sample_range", "repeat_render"])]
+    passes: Option<u32>,
+
+    /// Stop progressive refinement once a pass changes the image by less than
+    /// this mean absolute amount (e.g. `0.001`). Implies progressive mode,
+    /// capped at --passes or 256 passes
+    #[arg(long, value_parser = parse_positive,
+          conflicts_with_all = ["sample_range", "repeat_render"])]
+    converge: Option<f64>,
+
+    /// Redraw the image in place after every progressive pass, rewriting
+    /// only the cells that changed to keep traffic low over slow links such
+    /// as SSH. Implies progressive mode, capped at --passes or 256 passes
+    #[arg(long, conflicts_with_all = ["quiet", "sample_range", "repeat_render", "merge"])]
+    live: bool,
+
+    /// With --live, the per-channel color change (0–255) a cell needs before
+    /// it is redrawn. The final image is always drawn exactly
+    #[arg(long, default_value_t = 8)]
+    live_threshold: u8,
+
+    /// With --repeat-render, exclude the first (cold cache) run from timings
+    #[arg(long)]
+    discard_cold: bool,
+
+    /// Enable lightweight path guiding: learn which directions bring light
+    /// into each pixel and steer part of the first bounce toward them.
+    /// Unbiased; cuts noise in scenes with small or hard-to-reach lights
+    #[arg(long)]
+    guide: bool,
+
+    /// Show this linear RGB color (`r,g,b`) where camera rays miss the scene,
+    /// while the sky keeps lighting it and appearing in reflections
+    #[arg(long, value_parser = parse_vec3)]
+    background: Option<Vec3>,
+
+    /// Tint surfaces within a few percent of the focus distance green, to
+    /// set up depth of field before a long render
+    #[arg(long)]
+    focus_preview: bool,
+
+    /// Fast but biased GI preview: bake spherical-harmonic irradiance at a
+    /// sparse set of points and end diffuse paths on it after the first
+    /// bounce. Leave off for ground-truth path tracing to compare
+    #[arg(long)]
+    sh_cache: bool,
+
+    /// Clay render: shade every surface except lights as matte gray to
+    /// inspect form and lighting without material distraction
+    #[arg(long)]
+    clay: bool,
+
+    /// Sky lighting the scene: the preset's own, or an analytic daylight sky
+    /// (Preetham) with a sun disk, set by --sun-elevation/--sun-azimuth/--turbidity
+    #[arg(long, value_enum, default_value_t = CliSky::Preset)]
+    sky: CliSky,
+
+    /// Add a sun lamp to the sky that is sampled as a light, so it casts
+    /// shadows. Placed by --sun-elevation/--sun-azimuth; works with any sky
+    #[arg(long)]
+    sky_sun: bool,
+
+    /// Sky sun: angular diameter in degrees; larger suns cast softer shadows
+    #[arg(long, default_value_t = 1.0, value_parser = parse_sun_size, requires = "sky_sun")]
+    sun_size: f64,
+
+    /// Sky sun: irradiance on a surface facing it (a white sky of radiance 1
+    /// gives π)
+    #[arg(long, default_value_t = 8.0, value_parser = parse_positive, requires = "sky_sun")]
+    sun_strength: f64,
+
+    /// Physical sky and sky sun: sun height above the horizon in degrees (0–90]
+    #[arg(long, default_value_t = 30.0, value_parser = parse_elevation)]
+    sun_elevation: f64,
+
+    /// Physical sky and sky sun: sun direction around the vertical axis in degrees; 0
+    /// puts the sun behind the preset cameras (+z), 90 to their right (+x)
+    #[arg(long, default_value_t = 45.0, allow_hyphen_values = true)]
+    sun_azimuth: f64,
+
+    /// Physical sky: atmospheric haze, from 2 (clear) to 10 (hazy)
+    #[arg(long, default_value_t = 3.0, value_parser = parse_turbidity)]
+    turbidity: f64,
+
+    /// Scene size relative to the built-in presets (about ten units across),
+    /// scaling the self-intersection epsilon: e.g. `1000` for a scene in
+    /// millimeters. `auto` derives it from the diagonal of the scene's
+    /// bounding box, which a large ground sphere dominates
+    #[arg(long, default_value = "1", value_parser = parse_scene_scale)]
+    scene_scale: SceneScale,
 
     /// Terminal output encoding mode
-    #[arg(short, long, value_enum, default_value_t = CliOutputMode::Halfblock)]
+    #[arg(short, long, value_enum, default_value_t = CliOutputMode::Auto)]
     mode: CliOutputMode,
 
-    /// Tone mapping operator for HDR → LDR conversion
-    #[arg(short, long, value_enum, default_value_t = CliToneMap::None)]
-    tonemap: CliToneMap,
+    /// Terminal color correction applied before emitting ANSI colors (not to
+    /// file export), for terminals that clip or shift saturated colors
+    #[arg(long, value_enum, default_value_t = CliColorProfile::Identity)]
+    color_profile: CliColorProfile,
+
+    /// Custom terminal color matrix as nine comma-separated numbers, row
+    /// by row (e.g. `0.9,0.1,0,0,1,0,0,0,1`). Overrides --color-profile
+    #[arg(long, value_parser = parse_matrix, allow_hyphen_values = true)]
+    color_matrix: Option<[[f64; 3]; 3]>,
+
+    /// Lock the image to this aspect ratio (e.g. `16:9` or `2.39`) and pad the
+    /// remaining output with black bars instead of stretching the image
+    #[arg(long, value_parser = parse_ratio)]
+    frame_aspect: Option<f64>,
+
+    /// Terminal cell aspect as W:H (e.g. `1:2`, `1:2.2`, or `1:1` for square
+    /// cells). Corrects for the cell shape so the image is undistorted; the
+    /// camera aspect follows the corrected output size
+    #[arg(long, value_parser = parse_ratio)]
+    pixel_ar: Option<f64>,
+
+    /// Override the preset's camera position, as `x,y,z`
+    #[arg(long, value_parser = parse_vec3, allow_hyphen_values = true)]
+    look_from: Option<Vec3>,
+
+    /// Override the point the camera looks at, as `x,y,z`
+    #[arg(long, value_parser = parse_vec3, allow_hyphen_values = true)]
+    look_at: Option<Vec3>,
+
+    /// Turn the camera in place by `yaw,pitch[,roll]` degrees (positive yaw
+    /// turns left, positive pitch looks up), applied after --look-from/--look-at
+    #[arg(long, value_parser = parse_euler, allow_hyphen_values = true)]
+    rotate_camera: Option<(f64, f64, f64)>,
+
+    /// Override the vertical field of view in degrees (0–180, exclusive)
+    #[arg(long, value_parser = parse_fov)]
+    fov: Option<f64>,
+
+    /// Override the lens aperture diameter; 0 disables depth of field
+    #[arg(long, value_parser = parse_non_negative)]
+    aperture: Option<f64>,
+
+    /// Override the focus distance (must be positive)
+    #[arg(long, value_parser = parse_positive)]
+    focus: Option<f64>,
+
+    /// Focus on whatever lies under the frame center: a probe ray through
+    /// the center sets the focus distance before rendering
+    #[arg(long, conflicts_with = "focus")]
+    autofocus: bool,
+
+    /// Report the object under output pixel `X,Y` (counted from the top
+    /// left): its name, material and distance from the camera
+    #[arg(long, value_parser = parse_pixel, conflicts_with = "stereo")]
+    pick: Option<(u32, u32)>,
+
+    /// Fraction of the frame time the shutter stays open, from 0 to 1.
+    /// Moving objects (the showcase's bouncing spheres) blur across it
+    #[arg(long, default_value_t = 0.0, value_parser = parse_shutter)]
+    shutter: f64,
+
+    /// Render a stereo pair from two cameras --iod apart and combine them:
+    /// `anaglyph` (red/cyan glasses), `side-by-side` (parallel viewing) or
+    /// `cross` (cross-eyed viewing). Side-by-side output is twice --width
+    #[arg(long, value_enum, conflicts_with_all = ["live", "repeat_render", "guides"])]
+    stereo: Option<CliStereo>,
+
+    /// Interocular distance for --stereo, in scene units
+    #[arg(long, default_value_t = 0.065, value_parser = parse_positive, requires = "stereo")]
+    iod: f64,
+
+    /// Tone mapping operator for HDR → LDR conversion. Defaults to the
+    /// scene's recommendation (`none` for --merge)
+    #[arg(short, long, value_enum)]
+    tonemap: Option<CliToneMap>,
+
+    /// Braille/ASCII only: derive dot and density structure from the log
+    /// luminance of the linear HDR image instead of the tone-mapped one
+    #[arg(long)]
+    log_luminance: bool,
+
+    /// Mirror the image left↔right (applies to terminal and file output)
+    #[arg(long)]
+    flip_h: bool,
+
+    /// Mirror the image top↔bottom (applies to terminal and file output)
+    #[arg(long)]
+    flip_v: bool,
+
+    /// Composition guides drawn over the displayed image, comma-separated:
+    /// `thirds`, `center`, `safe` (e.g. `--guides thirds,center`)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    guides: Vec<CliGuide>,
+
+    /// Also draw --guides into the exported image
+    #[arg(long, requires = "guides")]
+    export_guides: bool,
+
+    /// Contrast around mid-gray applied after tone mapping (1.0 = unchanged)
+    #[arg(long, default_value_t = 1.0, value_parser = parse_non_negative)]
+    contrast: f64,
+
+    /// Saturation scale applied after tone mapping (0 = grayscale, 1.0 = unchanged)
+    #[arg(long, default_value_t = 1.0, value_parser = parse_non_negative)]
+    saturation: f64,
+
+    /// Grayscale output: reduce the final display-referred image to its
+    /// luminance (works with every --mode and with file export)
+    #[arg(long)]
+    mono: bool,
 
     /// Disable gamma correction (output linear radiance values directly)
     #[arg(long)]
     no_gamma: bool,
 
-    /// Save rendered image to a PPM file (in addition to terminal display)
+    /// Save rendered image to a PPM file (in addition to terminal display).
+    /// A `.photon` extension writes a partial accumulation file instead.
     #[arg(short, long)]
     output: Option<String>,
 
+    /// Render a turntable of N frames, orbiting the camera once around its
+    /// target. With -o, frames are saved as numbered files (`out.0001.ppm`, …)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..),
+          conflicts_with_all = ["sample_range", "repeat_render"])]
+    frames: u32,
+
+    /// Fixed RNG seed for reproducible renders (default: OS entropy). With
+    /// --sample-range every machine must pass the same seed
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Animation: reuse the same seed for every frame. Noise then stays put
+    /// on screen like dirt on the lens, which reads as calmer on static shots
+    #[arg(long, conflicts_with = "seed_per_frame")]
+    seed_fixed: bool,
+
+    /// Animation: derive each frame's seed from the base seed plus the frame
+    /// number (the default). Noise shimmers from frame to frame instead of
+    /// sticking to the screen, which suits camera moves
+    #[arg(long)]
+    seed_per_frame: bool,
+
+    /// Scale exposure so each frame's log-average luminance lands on mid-gray
+    /// before tone mapping
+    #[arg(long)]
+    auto_exposure: bool,
+
+    /// Compute auto-exposure on the first frame and hold it for every frame,
+    /// so an animation does not pump brighter and darker (implies --auto-exposure)
+    #[arg(long)]
+    lock_exposure: bool,
+
+    /// Render only this inclusive range of samples (e.g. `0-63`), for
+    /// splitting one render across machines. Each range is seeded from
+    /// --seed (default 0) plus its start so it is reproducible; save with
+    /// `-o part.photon`.
+    #[arg(long, value_parser = parse_sample_range, conflicts_with = "merge")]
+    sample_range: Option<(u32, u32)>,
+
+    /// Merge `.photon` partial renders into a final image instead of
+    /// rendering (e.g. `--merge a.photon b.photon -o final.ppm`)
+    #[arg(long, num_args = 1..)]
+    merge: Vec<String>,
+
+    /// Write the PPM export as human-readable ASCII (P3) instead of binary
+    /// P6, for small debug renders and golden files. Implied by a `.p3.ppm`
+    /// extension
+    #[arg(long)]
+    ppm_ascii: bool,
+
+    /// Apply Floyd–Steinberg dithering when quantizing the exported image to
+    /// 8 bits, removing banding on smooth gradients
+    #[arg(long)]
+    dither_export: bool,
+
+    /// Resize the exported image to `WxH` pixels (e.g. `320x180` for a
+    /// thumbnail), resampling the linear image: box-filtered when shrinking,
+    /// bilinear otherwise. The terminal display keeps the render size
+    #[arg(long, value_parser = parse_size, conflicts_with = "export_guides")]
+    export_size: Option<(u32, u32)>,
+
     /// Suppress terminal display (useful with --output for headless rendering)
     #[arg(long)]
     quiet: bool,
@@ -97,6 +414,8 @@ struct Cli {
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
 enum CliOutputMode {
+    /// Detect the richest mode the terminal supports
+    Auto,
     /// Unicode braille patterns — highest effective resolution (2×4 subpixel)
     Braille,
     /// Full-block characters with 24-bit true color
@@ -107,13 +426,121 @@ enum CliOutputMode {
     Ascii,
 }
 
-impl From<CliOutputMode> for OutputMode {
-    fn from(m: CliOutputMode) -> Self {
-        match m {
-            CliOutputMode::Braille => OutputMode::Braille,
-            CliOutputMode::Truecolor => OutputMode::TrueColor,
-            CliOutputMode::Halfblock => OutputMode::HalfBlock,
-            CliOutputMode::Ascii => OutputMode::Ascii,
+impl CliOutputMode {
+    /// Maps the flag to a concrete mode, probing the terminal for `auto`;
+    /// a probed mode comes with the reason it was chosen.
+    fn resolve(self) -> (OutputMode, Option<&'static str>) {
+        match self {
+            CliOutputMode::Auto => {
+                let (mode, reason) = OutputMode::detect();
+                (mode, Some(reason))
+            }
+            CliOutputMode::Braille => (OutputMode::Braille, None),
+            CliOutputMode::Truecolor => (OutputMode::TrueColor, None),
+            CliOutputMode::Halfblock => (OutputMode::HalfBlock, None),
+            CliOutputMode::Ascii => (OutputMode::Ascii, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SceneScale {
+    /// Derive the scale from the diagonal of the scene's bounding box.
+    Auto,
+    Factor(f64),
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliSky {
+    /// The sky defined by the scene preset
+    Preset,
+    /// Analytic daylight sky (Preetham) with a sun disk
+    Physical,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliGuide {
+    /// Rule-of-thirds grid
+    Thirds,
+    /// Cross at the frame center
+    Center,
+    /// Title-safe border, 10% in from each edge
+    Safe,
+}
+
+impl From<CliGuide> for Guide {
+    fn from(g: CliGuide) -> Self {
+        match g {
+            CliGuide::Thirds => Guide::Thirds,
+            CliGuide::Center => Guide::Center,
+            CliGuide::Safe => Guide::Safe,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliSampler {
+    /// Independent pseudorandom numbers
+    Random,
+    /// Halton sequence with per-pixel rotation
+    Halton,
+    /// Sobol sequence with per-pixel rotation
+    Sobol,
+}
+
+impl From<CliSampler> for SamplerKind {
+    fn from(s: CliSampler) -> Self {
+        match s {
+            CliSampler::Random => SamplerKind::Random,
+            CliSampler::Halton => SamplerKind::Halton,
+            CliSampler::Sobol => SamplerKind::Sobol,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliStereo {
+    /// Red/cyan anaglyph
+    Anaglyph,
+    /// Left eye on the left, for parallel viewing
+    SideBySide,
+    /// Right eye on the left, for cross-eyed viewing
+    Cross,
+}
+
+impl From<CliStereo> for Stereo {
+    fn from(s: CliStereo) -> Self {
+        match s {
+            CliStereo::Anaglyph => Stereo::Anaglyph,
+            CliStereo::SideBySide => Stereo::SideBySide,
+            CliStereo::Cross => Stereo::Cross,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliColorProfile {
+    /// No correction
+    Identity,
+    /// Pull all colors 20% toward gray, for terminals that over-saturate
+    Muted,
+    /// Blend a little green and blue into red, for terminals with harsh reds
+    TameReds,
+}
+
+impl CliColorProfile {
+    fn matrix(self) -> Option<[[f64; 3]; 3]> {
+        // Luminance-preserving desaturation: s·I + (1 − s)·[BT.709 weights].
+        let desaturate = |s: f64| {
+            let (r, g, b) = (0.2126 * (1.0 - s), 0.7152 * (1.0 - s), 0.0722 * (1.0 - s));
+            [[r + s, g, b], [r, g + s, b], [r, g, b + s]]
+        };
+        match self {
+            CliColorProfile::Identity => None,
+            CliColorProfile::Muted => Some(desaturate(0.8)),
+            CliColorProfile::TameReds => {
+                Some([[0.85, 0.10, 0.05], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+            }
         }
     }
 }
@@ -138,6 +565,187 @@ impl From<CliToneMap> for ToneMapOp {
     }
 }
 
+/// Parses an aspect ratio given either as `W:H` or as a plain decimal.
+fn parse_ratio(s: &str) -> Result<f64, String> {
+    let ratio = match s.split_once(':') {
+        Some((w, h)) => {
+            let w: f64 = w
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid width in '{s}'"))?;
+            let h: f64 = h
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid height in '{s}'"))?;
+            w / h
+        }
+        None => s
+            .trim()
+            .parse()
+            .map_err(|_| format!("expected W:H or a decimal ratio, got '{s}'"))?,
+    };
+    if ratio.is_finite() && ratio > 0.0 {
+        Ok(ratio)
+    } else {
+        Err(format!("ratio must be positive, got '{s}'"))
+    }
+}
+
+/// Parses a finite, non-negative number such as a grading factor.
+fn parse_non_negative(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v.is_finite() && v >= 0.0 => Ok(v),
+        _ => Err(format!("expected a non-negative number, got '{s}'")),
+    }
+}
+
+/// Parses a thread count, resolving `auto` to the available parallelism.
+fn parse_threads(s: &str) -> Result<usize, String> {
+    if s.trim() == "auto" {
+        return Ok(std::thread::available_parallelism().map_or(1, |n| n.get()));
+    }
+    match s.trim().parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(n),
+        _ => Err(format!("expected `auto` or a thread count ≥ 1, got '{s}'")),
+    }
+}
+
+/// Parses a finite, strictly positive number such as a distance.
+fn parse_positive(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v.is_finite() && v > 0.0 => Ok(v),
+        _ => Err(format!("expected a positive number, got '{s}'")),
+    }
+}
+
+/// Parses comma-separated finite numbers, e.g. `0,2.5,-3`.
+fn parse_components(s: &str) -> Result<Vec<f64>, String> {
+    s.split(',')
+        .map(|c| match c.trim().parse::<f64>() {
+            Ok(v) if v.is_finite() => Ok(v),
+            _ => Err(format!("invalid number '{}' in '{s}'", c.trim())),
+        })
+        .collect()
+}
+
+/// Parses a point or direction given as `x,y,z`.
+fn parse_vec3(s: &str) -> Result<Vec3, String> {
+    match parse_components(s)?[..] {
+        [x, y, z] => Ok(Vec3::new(x, y, z)),
+        _ => Err(format!("expected x,y,z, got '{s}'")),
+    }
+}
+
+/// Parses a row-major 3×3 matrix given as nine comma-separated numbers.
+fn parse_matrix(s: &str) -> Result<[[f64; 3]; 3], String> {
+    match parse_components(s)?[..] {
+        [a, b, c, d, e, f, g, h, i] => Ok([[a, b, c], [d, e, f], [g, h, i]]),
+        _ => Err(format!("expected nine numbers, got '{s}'")),
+    }
+}
+
+/// Parses camera Euler angles as `yaw,pitch` or `yaw,pitch,roll` degrees.
+fn parse_euler(s: &str) -> Result<(f64, f64, f64), String> {
+    match parse_components(s)?[..] {
+        [yaw, pitch] => Ok((yaw, pitch, 0.0)),
+        [yaw, pitch, roll] => Ok((yaw, pitch, roll)),
+        _ => Err(format!("expected yaw,pitch[,roll], got '{s}'")),
+    }
+}
+
+/// Parses a vertical field of view strictly between 0 and 180 degrees.
+fn parse_fov(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v > 0.0 && v < 180.0 => Ok(v),
+        _ => Err(format!("expected degrees between 0 and 180, got '{s}'")),
+    }
+}
+
+/// Parses `--scene-scale`: `auto` or a positive factor.
+fn parse_scene_scale(s: &str) -> Result<SceneScale, String> {
+    if s.trim() == "auto" {
+        return Ok(SceneScale::Auto);
+    }
+    parse_positive(s)
+        .map(SceneScale::Factor)
+        .map_err(|_| format!("expected `auto` or a positive scale, got '{s}'"))
+}
+
+/// Parses a sun elevation above the horizon, in (0, 90] degrees.
+fn parse_elevation(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v > 0.0 && v <= 90.0 => Ok(v),
+        _ => Err(format!("expected degrees in (0, 90], got '{s}'")),
+    }
+}
+
+/// Parses an image size given as `WxH`, both at least one pixel.
+fn parse_size(s: &str) -> Result<(u32, u32), String> {
+    let parsed = s
+        .split_once(['x', 'X'])
+        .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+    match parsed {
+        Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(format!("expected a size like '320x180', got '{s}'")),
+    }
+}
+
+/// Parses a shutter fraction in [0, 1].
+fn parse_shutter(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if (0.0..=1.0).contains(&v) => Ok(v),
+        _ => Err(format!(
+            "expected a shutter fraction between 0 and 1, got '{s}'"
+        )),
+    }
+}
+
+/// Parses pixel coordinates given as `X,Y`.
+fn parse_pixel(s: &str) -> Result<(u32, u32), String> {
+    let parsed = s
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)));
+    parsed.ok_or_else(|| format!("expected pixel coordinates like '40,12', got '{s}'"))
+}
+
+/// Parses a sun's angular diameter in degrees: positive and below a
+/// hemisphere.
+fn parse_sun_size(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(v) if v > 0.0 && v < 180.0 => Ok(v),
+        _ => Err(format!(
+            "expected an angular size between 0 and 180 degrees, got '{s}'"
+        )),
+    }
+}
+
+/// Parses a sky turbidity within the range the Preetham fit covers.
+fn parse_turbidity(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if (2.0..=10.0).contains(&v) => Ok(v),
+        _ => Err(format!("expected a turbidity between 2 and 10, got '{s}'")),
+    }
+}
+
+/// Parses an inclusive sample range such as `0-63`.
+fn parse_sample_range(s: &str) -> Result<(u32, u32), String> {
+    let (start, end) = s
+        .split_once('-')
+        .ok_or_else(|| format!("expected START-END, got '{s}'"))?;
+    let start: u32 = start
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid start in '{s}'"))?;
+    let end: u32 = end
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid end in '{s}'"))?;
+    if end < start {
+        return Err(format!("range end must not precede its start, got '{s}'"));
+    }
+    Ok((start, end))
+}
+
 fn print_header(scene_name: &str, config: &RenderConfig) {
     let mode_name = match config.output_mode {
         OutputMode::Braille => "Braille (2×4 subpixel)",
@@ -163,56 +771,554 @@ fn print_header(scene_name: &str, config: &RenderConfig) {
     eprintln!("  Samples:    {} spp", config.samples_per_pixel);
     eprintln!("  Bounces:    {}", config.max_bounces);
     eprintln!("  Tone map:   {tonemap_name}");
+    eprintln!("  Threads:    {}", config.threads);
+    match config.sampler {
+        SamplerKind::Random => {}
+        SamplerKind::Halton => eprintln!("  Sampler:    Halton (QMC)"),
+        SamplerKind::Sobol => eprintln!("  Sampler:    Sobol (QMC)"),
+    }
+    if config.downscale > 1 {
+        eprintln!("  Preview:    1/{} resolution", config.downscale);
+    }
+    if config.ray_epsilon != DEFAULT_RAY_EPSILON {
+        eprintln!(
+            "  Scale:      {:.3}× (ray epsilon {:.2e})",
+            config.scene_scale(),
+            config.ray_epsilon
+        );
+    }
+    if let Some(cap) = config.throttle {
+        eprintln!("  Throttle:   {cap} Mrays/s");
+    }
+    if config.pixel_aspect != 1.0 {
+        eprintln!(
+            "  Pixels:     {:.3}:1 on screen ({:.3}:1 output)",
+            config.pixel_aspect,
+            config.display_aspect()
+        );
+    }
+    if let Some(aspect) = config.frame_aspect {
+        let (_, _, w, h) = config.frame_rect();
+        eprintln!("  Frame:      {aspect:.3}:1 locked ({w}×{h} image area)");
+    }
     eprintln!();
 }
 
+/// Applies the CLI options that only affect how a finished HDR image is
+/// developed and shown, shared by rendering and `--merge`.
+fn apply_display_options(cli: &Cli, output_mode: OutputMode, config: &mut RenderConfig) {
+    config.output_mode = output_mode;
+    if let Some(tonemap) = cli.tonemap {
+        config.tone_map = tonemap.into();
+    }
+    config.gamma = !cli.no_gamma;
+    config.log_luminance = cli.log_luminance;
+    config.contrast = cli.contrast;
+    config.saturation = cli.saturation;
+    config.mono = cli.mono;
+    config.flip_horizontal = cli.flip_h;
+    config.flip_vertical = cli.flip_v;
+    config.guides = cli.guides.iter().map(|&g| g.into()).collect();
+    config.display_matrix = cli.color_matrix.or_else(|| cli.color_profile.matrix());
+}
+
+/// Overrides the preset's recommended sample count and bounce depth.
+fn apply_quality_options(cli: &Cli, config: &mut RenderConfig) {
+    if let Some(spp) = cli.spp {
+        config.samples_per_pixel = spp;
+    }
+    if let Some(bounces) = cli.bounces {
+        config.max_bounces = bounces;
+    }
+}
+
+/// Reframes the preset's camera from the CLI before the camera is built.
+fn apply_camera_options(cli: &Cli, camera: &mut CameraConfig) {
+    if let Some(from) = cli.look_from {
+        camera.look_from = from;
+    }
+    if let Some(at) = cli.look_at {
+        camera.look_at = at;
+    }
+    if let Some((yaw, pitch, roll)) = cli.rotate_camera {
+        camera.rotate(yaw, pitch, roll);
+    }
+    if let Some(fov) = cli.fov {
+        camera.vfov_degrees = fov;
+    }
+    if let Some(aperture) = cli.aperture {
+        camera.aperture = aperture;
+    }
+    if let Some(focus) = cli.focus {
+        camera.focus_dist = focus;
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
-    let scene_desc = cli.scene.build();
+    if !cli.merge.is_empty() {
+        merge_partials(&cli);
+        return;
+    }
+
+    let mut scene_desc = cli.scene.build();
     let scene_name = scene_desc.name;
 
-    let (world, camera, sky, mut config) = presets::build_world(scene_desc);
+    let (output_mode, detected) = cli.mode.resolve();
+    let pixel_aspect = cli
+        .pixel_ar
+        .map_or(1.0, |cell| output_mode.pixel_aspect(cell));
+
+    apply_camera_options(&cli, &mut scene_desc.camera_config);
+
+    // A locked frame aspect replaces the preset's camera aspect so the image
+    // inside the letterbox is undistorted. With an explicit cell aspect the
+    // camera instead matches the on-screen shape of the whole output.
+    if let Some(aspect) = cli.frame_aspect {
+        scene_desc.camera_config.aspect_ratio = aspect;
+    } else if cli.pixel_ar.is_some() {
+        scene_desc.camera_config.aspect_ratio = cli.width as f64 * pixel_aspect / cli.height as f64;
+    }
+
+    let scene_stats = cli.print_scene_stats.then(|| scene_desc.stats());
+    let mut camera_config = scene_desc.camera_config;
+    let (world, mut camera, mut sky, mut config, mut lights) =
+        presets::build_world(scene_desc, cli.bvh_leaf_size as usize);
+    camera = camera.with_shutter(0.0, cli.shutter);
+    if let CliSky::Physical = cli.sky {
+        sky = SkyModel::Physical(PhysicalSky::new(
+            sun_direction(cli.sun_elevation, cli.sun_azimuth),
+            cli.turbidity,
+        ));
+    }
+    // The lamp takes the physical sky's sun color, normalized to unit
+    // luminance so --sun-strength alone sets its brightness.
+    let sun = cli.sky_sun.then(|| {
+        let tint = match sky {
+            SkyModel::Physical(physical) => {
+                let color = physical.sun_color();
+                color / color.luminance().max(1e-9)
+            }
+            _ => Color::ones(),
+        };
+        SkySun::new(
+            sun_direction(cli.sun_elevation, cli.sun_azimuth),
+            cli.sun_size,
+            tint * cli.sun_strength,
+        )
+    });
+    if let Some(sun) = sun {
+        lights.push(LightHandle::Sun(sun));
+        if let SkyModel::Physical(physical) = sky {
+            sky = SkyModel::Physical(physical.without_sun_disk());
+        }
+    }
 
     // Override config with CLI arguments
     config.width = cli.width;
     config.height = cli.height;
-    config.samples_per_pixel = cli.spp;
-    config.max_bounces = cli.bounces;
-    config.output_mode = cli.mode.into();
-    config.tone_map = cli.tonemap.into();
-    config.gamma = !cli.no_gamma;
+    apply_quality_options(&cli, &mut config);
+    config.sampler = cli.sampler.into();
+    config.frame_aspect = cli.frame_aspect;
+    config.pixel_aspect = pixel_aspect;
+    config.guiding = cli.guide;
+    config.clay = cli.clay;
+    config.focus_preview = cli.focus_preview;
+    config.sh_cache = cli.sh_cache;
+    config.downscale = cli.preview_downscale;
+    let scene_scale = match cli.scene_scale {
+        SceneScale::Auto => extent_scale(&world.bounding_box()),
+        SceneScale::Factor(scale) => scale,
+    };
+    config.ray_epsilon = DEFAULT_RAY_EPSILON * scene_scale;
+    config.threads = cli.threads;
+    config.throttle = cli.throttle;
+    config.background = cli.background;
+    apply_display_options(&cli, output_mode, &mut config);
+
+    if let Some((start, end)) = cli.sample_range {
+        config.samples_per_pixel = end - start + 1;
+        config.seed = Some(cli.seed.unwrap_or(0));
+        config.first_sample = start;
+    }
+
+    if let Err(e) = config.validate() {
+        eprintln!("  Error: {e}");
+        std::process::exit(1);
+    }
+    if let Some((x, y)) = cli
+        .pick
+        .filter(|&(x, y)| x >= config.width || y >= config.height)
+    {
+        eprintln!(
+            "  Error: --pick {x},{y} lies outside the {}×{} image",
+            config.width, config.height
+        );
+        std::process::exit(1);
+    }
+
+    // The preset's camera is rebuilt around the probed distance; on a miss
+    // it keeps the configured one.
+    let autofocus = cli
+        .autofocus
+        .then(|| probe_focus(&world, &camera_config, config.ray_epsilon));
+    if let Some(Some(distance)) = autofocus {
+        camera_config.focus_dist = distance;
+        camera = build_camera(&cli, &camera_config);
+    }
 
     print_header(scene_name, &config);
+    if let Some(reason) = detected {
+        eprintln!("  Mode:       auto — {reason}");
+    }
+    if let Some((start, end)) = cli.sample_range {
+        eprintln!("  Partial:    samples {start}–{end}");
+    }
 
     // Print BVH diagnostics
     eprintln!(
-        "  BVH:        {} objects, depth {}",
+        "  BVH:        {} objects, depth {}, ≤{} per leaf",
         world.leaf_count(),
-        world.depth()
+        world.depth(),
+        config.max_leaf_size
     );
+    if lights.is_empty() {
+        eprintln!("  Lights:     0");
+    } else {
+        let labels: Vec<&str> = lights.iter().map(LightHandle::label).collect();
+        eprintln!("  Lights:     {} ({})", lights.len(), labels.join(", "));
+    }
+    if let Some(stats) = &scene_stats {
+        stats.print();
+    }
+    match autofocus {
+        Some(Some(distance)) => eprintln!("  Focus:      {distance:.3} (autofocus)"),
+        Some(None) => eprintln!(
+            "  Warning:    autofocus ray missed; keeping focus distance {:.3}",
+            camera_config.focus_dist
+        ),
+        None => {}
+    }
+    if let Some((x, y)) = cli.pick {
+        eprintln!(
+            "  Pick:       ({x}, {y}) → {}",
+            describe_pick(&world, &camera, &config, x, y)
+        );
+    }
+    if cli.frames > 1 {
+        eprintln!("  Frames:     {} (turntable)", cli.frames);
+    }
     eprintln!();
 
-    let tracer = PathTracer {
-        scene: &world,
-        config: &config,
-        camera: &camera,
-        sky,
+    let spp = config.samples_per_pixel;
+    // A fixed seed needs a base even when none was given.
+    let base_seed = match cli.seed {
+        Some(seed) => Some(seed),
+        None if cli.seed_fixed => Some(rand::random()),
+        None => None,
     };
+    let mut locked_exposure = None;
+    for frame in 0..cli.frames {
+        if cli.frames > 1 {
+            eprintln!("  Frame {}/{}", frame + 1, cli.frames);
+        }
+        config.samples_per_pixel = spp;
+        if cli.sample_range.is_none() {
+            config.seed = base_seed.map(|seed| frame_seed(seed, frame, cli.seed_fixed));
+        }
+        let orbit = 360.0 * f64::from(frame) / f64::from(cli.frames);
+        let frame_config = camera_config.orbited(orbit);
+        let frame_camera = (frame > 0).then(|| build_camera(&cli, &frame_config));
+        let tracer = PathTracer {
+            scene: &world,
+            config: &config,
+            camera: frame_camera.as_ref().unwrap_or(&camera),
+            sky,
+            sun,
+            lights: &lights,
+            irradiance: None,
+        };
+        let cache = config.sh_cache.then(|| {
+            let t0 = std::time::Instant::now();
+            let cache = tracer.bake_irradiance();
+            eprintln!(
+                "  SH cache:   {} records in {:.2}s (biased preview)",
+                cache.len(),
+                t0.elapsed().as_secs_f64()
+            );
+            cache
+        });
+        let tracer = PathTracer {
+            irradiance: cache.as_ref(),
+            ..tracer
+        };
+
+        let mut live = cli.live.then(|| LiveDisplay::new(cli.live_threshold));
+        let (mut hdr, stats) = if let Some(mode) = cli.stereo {
+            render_stereo(&cli, &tracer, &frame_config, mode.into())
+        } else if cli.passes.is_some() || cli.converge.is_some() || cli.live {
+            let mut preview = |report: &str, mean: &Framebuffer| {
+                if let Some(live) = live.as_mut() {
+                    let mut hdr = mean.clone();
+                    hdr.flip(config.flip_horizontal, config.flip_vertical);
+                    let mut display = hdr.post_process(&config);
+                    display.draw_guides(&config);
+                    live.draw(&display, &hdr, &config, report);
+                }
+            };
+            tracer.render_progressive(
+                cli.passes.unwrap_or(DEFAULT_MAX_PASSES),
+                cli.converge,
+                cli.live.then_some(&mut preview as &mut PassPreview),
+            )
+        } else {
+            tracer.render()
+        };
+        let mut runs = vec![stats];
+        for _ in 1..cli.repeat_render {
+            // The BVH and camera are reused; only the last image is kept.
+            let (fb, stats) = tracer.render();
+            hdr = fb;
+            runs.push(stats);
+        }
+
+        // Progressive renders may stop early; export the samples actually taken.
+        config.samples_per_pixel = runs.last().unwrap().spp;
+        let exposure = (cli.auto_exposure || cli.lock_exposure)
+            .then(|| locked_exposure.unwrap_or_else(|| hdr.auto_exposure(&config)));
+        if let Some(exposure) = exposure {
+            if cli.lock_exposure {
+                locked_exposure = Some(exposure);
+            }
+            config.exposure = exposure;
+        }
+        // The live preview ends on the exact final image, before any other
+        // output moves the cursor away from it.
+        if let Some(live) = live.as_mut() {
+            let mut shown = hdr.clone();
+            shown.flip(config.flip_horizontal, config.flip_vertical);
+            let mut display = shown.post_process(&config);
+            display.draw_guides(&config);
+            live.finish(&display, &shown, &config, "  Final");
+        }
+
+        eprintln!();
+        runs.last().unwrap().print_summary();
+        if runs.len() > 1 {
+            let timed = if cli.discard_cold {
+                &runs[1..]
+            } else {
+                &runs[..]
+            };
+            RenderStats::print_benchmark(timed);
+        }
+        eprintln!();
+
+        if let Some(exposure) = exposure {
+            eprintln!("  Exposure: {exposure:.3}×");
+        }
+        let output = cli.output.as_deref().map(|path| {
+            if cli.frames > 1 {
+                frame_path(path, frame + 1)
+            } else {
+                path.to_string()
+            }
+        });
+        present(&cli, &config, hdr, output.as_deref());
+    }
+}
+
+/// Builds the camera for `config` with the CLI's shutter.
+fn build_camera(cli: &Cli, config: &CameraConfig) -> Camera {
+    Camera::new(config).with_shutter(0.0, cli.shutter)
+}
+
+/// `--stereo`: renders the frame from two eyes --iod apart, sharing the
+/// tracer's scene and cache, and combines them. Each eye is flipped on its
+/// own, since mirroring the side-by-side pair would also swap the eyes. The
+/// stats cover both eyes.
+fn render_stereo(
+    cli: &Cli,
+    tracer: &PathTracer,
+    camera_config: &CameraConfig,
+    mode: Stereo,
+) -> (Framebuffer, RenderStats) {
+    let [(left, left_stats), (right, right_stats)] = [-0.5, 0.5].map(|side| {
+        let camera = build_camera(cli, &camera_config.shifted_right(side * cli.iod));
+        let eye = PathTracer {
+            camera: &camera,
+            ..*tracer
+        };
+        let (mut image, stats) = if cli.passes.is_some() || cli.converge.is_some() {
+            eye.render_progressive(cli.passes.unwrap_or(DEFAULT_MAX_PASSES), cli.converge, None)
+        } else {
+            eye.render()
+        };
+        image.flip(cli.flip_h, cli.flip_v);
+        (image, stats)
+    });
+    let image = Framebuffer::stereo(&left, &right, mode);
+    let stats = RenderStats {
+        total_rays: left_stats.total_rays + right_stats.total_rays,
+        elapsed_secs: left_stats.elapsed_secs + right_stats.elapsed_secs,
+        width: image.width,
+        height: image.height,
+        spp: left_stats.spp.min(right_stats.spp),
+    };
+    (image, stats)
+}
+
+/// `--autofocus`: distance to the first surface along the ray through the
+/// frame center, which is the camera's viewing axis. `None` if it misses.
+fn probe_focus(world: &BvhNode, camera: &CameraConfig, epsilon: f64) -> Option<f64> {
+    let axis = (camera.look_at - camera.look_from).normalized();
+    world
+        .hit(&Ray::new(camera.look_from, axis), epsilon, f64::INFINITY)
+        .map(|hit| hit.t)
+}
+
+/// `--pick`: what the camera sees through the center of output pixel
+/// (`x`, `y`), undoing the output flips and the letterbox of a locked frame.
+fn describe_pick(
+    world: &BvhNode,
+    camera: &Camera,
+    config: &RenderConfig,
+    x: u32,
+    y: u32,
+) -> String {
+    let x = if config.flip_horizontal {
+        config.width - 1 - x
+    } else {
+        x
+    };
+    let y = if config.flip_vertical {
+        config.height - 1 - y
+    } else {
+        y
+    };
+    let (x0, y0, w, h) = config.frame_rect();
+    if !(x0..x0 + w).contains(&x) || !(y0..y0 + h).contains(&y) {
+        return "letterbox".into();
+    }
+    // Image rows run top-down; the film's t axis runs bottom-up.
+    let (u, v) = film_uv(
+        f64::from(x - x0) + 0.5,
+        f64::from(h - 1 - (y - y0)) + 0.5,
+        w,
+        h,
+    );
+    let ray = camera.center_ray(u, v);
+    match world.hit(&ray, config.ray_epsilon, f64::INFINITY) {
+        Some(hit) => format!(
+            "{} ({}) at distance {:.3}",
+            hit.object.unwrap_or("unnamed object"),
+            hit.material.label(),
+            hit.t * ray.direction.length()
+        ),
+        None => "sky".into(),
+    }
+}
+
+/// Unit vector toward a sun at `elevation` degrees above the horizon and
+/// `azimuth` degrees from +z toward +x.
+fn sun_direction(elevation: f64, azimuth: f64) -> Vec3 {
+    let (el, az) = (elevation.to_radians(), azimuth.to_radians());
+    Vec3::new(el.cos() * az.sin(), el.sin(), el.cos() * az.cos())
+}
+
+/// Seed for one animation frame. Per-frame seeds put the frame number in
+/// the high bits, so they never collide with the sample-index offsets that
+/// progressive passes add to the seed.
+fn frame_seed(base: u64, frame: u32, fixed: bool) -> u64 {
+    if fixed {
+        base
+    } else {
+        base.wrapping_add(u64::from(frame) << 32)
+    }
+}
+
+/// Numbers a sequence frame: `out.ppm` → `out.0001.ppm`.
+fn frame_path(path: &str, frame: u32) -> String {
+    match path.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.contains('/') => {
+            format!("{stem}.{frame:04}.{ext}")
+        }
+        _ => format!("{path}.{frame:04}"),
+    }
+}
+
+/// `--merge`: sums partial accumulation files into the final image.
+fn merge_partials(cli: &Cli) {
+    let (hdr, samples) = match Framebuffer::merge_accumulations(&cli.merge) {
+        Ok(merged) => merged,
+        Err(e) => {
+            eprintln!("  Error: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut config = RenderConfig {
+        width: hdr.width,
+        height: hdr.height,
+        samples_per_pixel: samples,
+        ..Default::default()
+    };
+    apply_display_options(cli, cli.mode.resolve().0, &mut config);
 
-    let (framebuffer, stats) = tracer.render();
     eprintln!();
-    stats.print_summary();
+    eprintln!(
+        "  Merged:     {} partials → {}×{} @ {} spp",
+        cli.merge.len(),
+        hdr.width,
+        hdr.height,
+        samples
+    );
     eprintln!();
 
-    // Terminal display
-    if !cli.quiet {
-        display_framebuffer(&framebuffer, config.output_mode);
+    present(cli, &config, hdr, cli.output.as_deref());
+}
+
+/// Develops the HDR image, shows it in the terminal and writes `--output`.
+fn present(cli: &Cli, config: &RenderConfig, mut hdr: Framebuffer, output: Option<&str>) {
+    // Stereo eyes arrive already flipped.
+    if cli.stereo.is_none() {
+        hdr.flip(config.flip_horizontal, config.flip_vertical);
+    }
+    let framebuffer = hdr.post_process(config);
+    let mut guided = framebuffer.clone();
+    guided.draw_guides(config);
+
+    // Terminal display (a live preview has already drawn the final image)
+    if !cli.quiet && !cli.live {
+        display_framebuffer(&guided, &hdr, config);
     }
 
-    // PPM export
-    if let Some(ref path) = cli.output {
-        match framebuffer.write_ppm(path) {
+    // PPM / partial accumulation export
+    if let Some(path) = output {
+        let result = if path.ends_with(".photon") {
+            hdr.write_accumulation(path, config.samples_per_pixel)
+        } else {
+            let resized = cli.export_size.map(|(width, height)| {
+                let filter = if width <= hdr.width && height <= hdr.height {
+                    ResampleFilter::Box
+                } else {
+                    ResampleFilter::Bilinear
+                };
+                hdr.resample(width, height, filter).post_process(config)
+            });
+            let image = if cli.export_guides {
+                &guided
+            } else {
+                resized.as_ref().unwrap_or(&framebuffer)
+            };
+            if cli.ppm_ascii || path.ends_with(".p3.ppm") {
+                image.write_ppm_ascii(path, cli.dither_export)
+            } else {
+                image.write_ppm(path, cli.dither_export)
+            }
+        };
+        match result {
             Ok(()) => eprintln!("  Saved: {path}"),
             Err(e) => eprintln!("  Error saving {path}: {e}"),
         }
@@ -221,3 +1327,38 @@ fn main() {
     eprintln!();
     eprintln!("  Rendered with photon-cli v{}", env!("CARGO_PKG_VERSION"));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset_config(args: &[&str]) -> RenderConfig {
+        let cli = Cli::try_parse_from(["photon-cli"].iter().chain(args)).unwrap();
+        let (_, _, _, mut config, _) = presets::build_world(cli.scene.build(), 1);
+        apply_quality_options(&cli, &mut config);
+        apply_display_options(&cli, cli.mode.resolve().0, &mut config);
+        config
+    }
+
+    #[test]
+    fn cli_flags_override_the_preset_quality() {
+        let config = preset_config(&["--scene", "cornell", "--mode", "ascii"]);
+        assert_eq!(config.samples_per_pixel, 256);
+        assert_eq!(config.max_bounces, 16);
+        assert_eq!(config.tone_map, ToneMapOp::Aces);
+
+        let config = preset_config(&[
+            "--scene",
+            "cornell",
+            "--mode",
+            "ascii",
+            "--spp",
+            "8",
+            "--tonemap",
+            "none",
+        ]);
+        assert_eq!(config.samples_per_pixel, 8);
+        assert_eq!(config.max_bounces, 16);
+        assert_eq!(config.tone_map, ToneMapOp::None);
+    }
+}
@@ -141,6 +141,25 @@ impl Vec3 {
         Self::new(self.x.sqrt(), self.y.sqrt(), self.z.sqrt())
     }
 
+    /// Relative luminance using the BT.709 primaries — the perceptual brightness
+    /// of a linear RGB color, used by the grayscale display encoders.
+    #[inline(always)]
+    pub fn luminance(self) -> f64 {
+        0.2126 * self.x + 0.7152 * self.y + 0.0722 * self.z
+    }
+
+    /// Scales the vector down to length `max` if it is longer, keeping its
+    /// direction; shorter vectors are returned unchanged.
+    #[inline]
+    pub fn clamp_length(self, max: f64) -> Self {
+        let len_sq = self.length_squared();
+        if len_sq > max * max {
+            self * (max / len_sq.sqrt())
+        } else {
+            self
+        }
+    }
+
     /// Checks if the vector is near-zero in all components, used to avoid
     /// degenerate scatter directions that would produce NaN in subsequent math.
     #[inline(always)]
@@ -149,6 +168,15 @@ impl Vec3 {
         self.x.abs() < EPS && self.y.abs() < EPS && self.z.abs() < EPS
     }
 
+    /// Rotates `self` by `angle` radians about the (normalized) `axis`, using
+    /// Rodrigues' rotation formula:
+    ///   v' = v·cos θ + (k × v)·sin θ + k·(k · v)·(1 − cos θ)
+    pub fn rotate_about(self, axis: Self, angle: f64) -> Self {
+        let k = axis.normalized();
+        let (sin, cos) = angle.sin_cos();
+        self * cos + k.cross(self) * sin + k * (k.dot(self) * (1.0 - cos))
+    }
+
     /// Converts a [0,1] color to an 8-bit RGB triple for ANSI true-color output.
     pub fn to_rgb8(self) -> (u8, u8, u8) {
         let c = self.saturate();
@@ -174,23 +202,25 @@ impl Vec3 {
         }
     }
 
-    /// Cosine-weighted hemisphere sampling via rejection + normalization.
-    /// Produces directions distributed proportionally to cos(θ), which is
-    /// the optimal importance sampling strategy for Lambertian BRDFs.
-    /// Generates a random unit vector via rejection sampling on the unit sphere.
+    /// Uniformly distributed random unit vector, from exactly two draws:
+    /// z uniform in [−1, 1] and a uniform azimuth (Archimedes' hat-box
+    /// theorem). Added to a normal it gives cosine-weighted directions, the
+    /// importance sampling for Lambertian BRDFs. A fixed draw count keeps
+    /// quasi-Monte Carlo dimensions aligned across samples.
     pub fn random_unit_vector(rng: &mut dyn rand::RngCore) -> Self {
-        Self::random_in_unit_sphere(rng).normalized()
+        let z = 1.0 - 2.0 * rng.gen::<f64>();
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = std::f64::consts::TAU * rng.gen::<f64>();
+        Self::new(r * phi.cos(), r * phi.sin(), z)
     }
 
-    /// Random point on the unit disk — used for depth-of-field simulation
-    /// by jittering the camera ray origin across the lens aperture.
+    /// Uniform random point on the unit disk from two draws (polar mapping
+    /// with r = √u) — used for depth-of-field simulation by jittering the
+    /// camera ray origin across the lens aperture.
     pub fn random_in_unit_disk(rng: &mut dyn rand::RngCore) -> Self {
-        loop {
-            let v = Self::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0.0);
-            if v.length_squared() < 1.0 {
-                return v;
-            }
-        }
+        let r = rng.gen::<f64>().sqrt();
+        let theta = std::f64::consts::TAU * rng.gen::<f64>();
+        Self::new(r * theta.cos(), r * theta.sin(), 0.0)
     }
 }
 
@@ -289,6 +319,170 @@ impl Index<usize> for Vec3 {
     }
 }
 
+// ─── 3×3 Matrix ─────────────────────────────────────────────────────────────
+
+/// A row-major 3×3 matrix for linear transforms (rotation, scale, color
+/// matrices). Points and directions transform as `m * v`; surface normals
+/// must use `inverse_transpose()` so they stay perpendicular to the surface
+/// under non-uniform scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub struct Mat3 {
+    pub rows: [[f64; 3]; 3],
+}
+
+#[allow(dead_code)]
+impl Mat3 {
+    pub const fn new(rows: [[f64; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    pub const fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Non-uniform scale along the coordinate axes.
+    pub const fn scale(s: Vec3) -> Self {
+        Self::new([[s.x, 0.0, 0.0], [0.0, s.y, 0.0], [0.0, 0.0, s.z]])
+    }
+
+    /// Rotation by `angle` radians about `axis` — the matrix form of
+    /// `Vec3::rotate_about`.
+    pub fn rotation(axis: Vec3, angle: f64) -> Self {
+        let k = axis.normalized();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+        Self::new([
+            [
+                cos + k.x * k.x * t,
+                k.x * k.y * t - k.z * sin,
+                k.x * k.z * t + k.y * sin,
+            ],
+            [
+                k.y * k.x * t + k.z * sin,
+                cos + k.y * k.y * t,
+                k.y * k.z * t - k.x * sin,
+            ],
+            [
+                k.z * k.x * t - k.y * sin,
+                k.z * k.y * t + k.x * sin,
+                cos + k.z * k.z * t,
+            ],
+        ])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let r = &self.rows;
+        Self::new([
+            [r[0][0], r[1][0], r[2][0]],
+            [r[0][1], r[1][1], r[2][1]],
+            [r[0][2], r[1][2], r[2][2]],
+        ])
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let r = &self.rows;
+        r[0][0] * (r[1][1] * r[2][2] - r[1][2] * r[2][1])
+            - r[0][1] * (r[1][0] * r[2][2] - r[1][2] * r[2][0])
+            + r[0][2] * (r[1][0] * r[2][1] - r[1][1] * r[2][0])
+    }
+
+    /// Matrix inverse via the adjugate. Returns `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let r = &self.rows;
+        let inv_det = 1.0 / det;
+        let cofactor = |a: usize, b: usize, c: usize, d: usize| {
+            (r[a][b] * r[c][d] - r[a][d] * r[c][b]) * inv_det
+        };
+        Some(Self::new([
+            [
+                cofactor(1, 1, 2, 2),
+                cofactor(0, 2, 2, 1),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                cofactor(1, 2, 2, 0),
+                cofactor(0, 0, 2, 2),
+                cofactor(0, 2, 1, 0),
+            ],
+            [
+                cofactor(1, 0, 2, 1),
+                cofactor(0, 1, 2, 0),
+                cofactor(0, 0, 1, 1),
+            ],
+        ]))
+    }
+
+    /// The normal matrix (M⁻¹)ᵀ. Returns `None` for singular matrices.
+    pub fn inverse_transpose(&self) -> Option<Self> {
+        self.inverse().map(|m| m.transpose())
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+    #[inline(always)]
+    fn mul(self, v: Vec3) -> Vec3 {
+        let r = &self.rows;
+        Vec3::new(
+            r[0][0] * v.x + r[0][1] * v.y + r[0][2] * v.z,
+            r[1][0] * v.x + r[1][1] * v.y + r[1][2] * v.z,
+            r[2][0] * v.x + r[2][1] * v.y + r[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+    fn mul(self, rhs: Mat3) -> Mat3 {
+        let mut out = [[0.0; 3]; 3];
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.rows[i][k] * rhs.rows[k][j]).sum();
+            }
+        }
+        Mat3::new(out)
+    }
+}
+
+// ─── Orthonormal Basis ──────────────────────────────────────────────────────
+
+/// A right-handed orthonormal basis (u, v, w) built around a single axis `w`.
+/// Used to map directions sampled in a canonical local frame (e.g. a cone
+/// about +z) into world space.
+#[derive(Debug, Clone, Copy)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    /// Builds a basis whose `w` axis is the (normalized) given direction.
+    /// The helper axis is chosen to avoid a degenerate cross product.
+    pub fn from_w(n: Vec3) -> Self {
+        let w = n.normalized();
+        let a = if w.x.abs() > 0.9 {
+            Vec3::unit_y()
+        } else {
+            Vec3::unit_x()
+        };
+        let v = w.cross(a).normalized();
+        let u = w.cross(v);
+        Self { u, v, w }
+    }
+
+    /// Transforms local coordinates (a, b, c) into world space.
+    #[inline(always)]
+    pub fn local(&self, a: f64, b: f64, c: f64) -> Vec3 {
+        self.u * a + self.v * b + self.w * c
+    }
+}
+
 // ─── Ray ────────────────────────────────────────────────────────────────────
 
 /// A parametric ray R(t) = origin + t · direction, the fundamental geometric
@@ -297,12 +491,24 @@ impl Index<usize> for Vec3 {
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
+    /// Instant within the camera shutter at which the ray travels. Every
+    /// segment of a path shares its primary ray's time.
+    pub time: f64,
 }
 
 impl Ray {
     #[inline(always)]
     pub const fn new(origin: Point3, direction: Vec3) -> Self {
-        Self { origin, direction }
+        Self::with_time(origin, direction, 0.0)
+    }
+
+    #[inline(always)]
+    pub const fn with_time(origin: Point3, direction: Vec3, time: f64) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+        }
     }
 
     /// Evaluates the ray at parameter t. Positive t gives points ahead of the origin.
@@ -364,8 +570,33 @@ impl Aabb {
         Aabb::new(min, max)
     }
 
+    /// Grows every axis thinner than `min_thickness` to that thickness,
+    /// keeping it centered. Flat primitives lying in a coordinate plane
+    /// would otherwise get a zero-thickness slab, which grazing and
+    /// axis-aligned rays can slip through.
+    pub fn pad(self, min_thickness: f64) -> Aabb {
+        let widen = |lo: f64, hi: f64| {
+            if hi - lo >= min_thickness {
+                (lo, hi)
+            } else {
+                let mid = 0.5 * (lo + hi);
+                (mid - 0.5 * min_thickness, mid + 0.5 * min_thickness)
+            }
+        };
+        let (x0, x1) = widen(self.min.x, self.max.x);
+        let (y0, y1) = widen(self.min.y, self.max.y);
+        let (z0, z1) = widen(self.min.z, self.max.z);
+        Aabb::new(Point3::new(x0, y0, z0), Point3::new(x1, y1, z1))
+    }
+
+    /// The center point of the box — the representative position of a
+    /// primitive when partitioning during BVH construction.
+    pub fn centroid(&self) -> Point3 {
+        (self.min + self.max) * 0.5
+    }
+
     /// Returns the index of the longest axis (0=x, 1=y, 2=z) — used as the
-    /// split dimension during top-down BVH construction with the midpoint heuristic.
+    /// split dimension during top-down BVH construction.
     pub fn longest_axis(&self) -> usize {
         let dx = self.max.x - self.min.x;
         let dy = self.max.y - self.min.y;
@@ -379,3 +610,54 @@ impl Aabb {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn rotating_by_full_turn_is_identity() {
+        let v = Vec3::new(0.3, -1.2, 2.5);
+        for axis in [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(1.0, 2.0, -0.5),
+        ] {
+            assert_close(v.rotate_about(axis, TAU), v);
+            assert_close(Mat3::rotation(axis, TAU) * v, v);
+        }
+    }
+
+    #[test]
+    fn inverse_transpose_keeps_normals_perpendicular() {
+        let m =
+            Mat3::rotation(Vec3::new(1.0, 1.0, 0.0), 0.7) * Mat3::scale(Vec3::new(3.0, 0.5, 1.0));
+        let normal_matrix = m.inverse_transpose().unwrap();
+        // Two tangents of a tilted plane and its normal.
+        let tangent_a = Vec3::new(1.0, 1.0, 0.0);
+        let tangent_b = Vec3::new(0.0, 1.0, -2.0);
+        let normal = tangent_a.cross(tangent_b);
+
+        let n = normal_matrix * normal;
+        assert!((m * tangent_a).dot(n).abs() < 1e-9);
+        assert!((m * tangent_b).dot(n).abs() < 1e-9);
+        // Transforming the normal like a direction would tilt it.
+        assert!((m * tangent_a).dot(m * normal).abs() > 1e-3);
+    }
+
+    #[test]
+    fn clamp_length_bounds_length_and_keeps_direction() {
+        let long = Vec3::new(3.0, -4.0, 12.0);
+        let clamped = long.clamp_length(2.0);
+        assert!((clamped.length() - 2.0).abs() < 1e-12);
+        assert_close(clamped.normalized(), long.normalized());
+
+        let short = Vec3::new(0.1, 0.2, -0.3);
+        assert_close(short.clamp_length(2.0), short);
+    }
+}
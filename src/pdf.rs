@@ -0,0 +1,222 @@
+use crate::math::*;
+use crate::scene::{HitRecord, LightHandle};
+use rand::Rng;
+
+// ─── Pdf Trait ──────────────────────────────────────────────────────────────
+
+/// A direction-sampling strategy at a shading point, described by the
+/// solid-angle density it samples from. `generate` draws a (not necessarily
+/// normalized) direction and `value` evaluates the density of any direction,
+/// which is what multiple importance sampling needs to weigh strategies.
+pub trait Pdf {
+    fn value(&self, direction: Vec3) -> f64;
+    fn generate(&self, rng: &mut dyn rand::RngCore) -> Vec3;
+}
+
+// ─── BRDF Sampling ──────────────────────────────────────────────────────────
+
+/// The material's own importance sampling. `scatter` has already drawn
+/// `sampled` from this distribution, so `generate` hands that direction
+/// back; `value` is the material's `scattering_pdf`.
+pub struct ScatterPdf<'a> {
+    pub ray: &'a Ray,
+    pub hit: &'a HitRecord<'a>,
+    pub sampled: Vec3,
+}
+
+impl Pdf for ScatterPdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        let scattered = Ray::with_time(self.hit.point, direction, self.ray.time);
+        self.hit
+            .material
+            .scattering_pdf(self.ray, self.hit, &scattered)
+    }
+
+    fn generate(&self, _rng: &mut dyn rand::RngCore) -> Vec3 {
+        self.sampled
+    }
+}
+
+// ─── Light Sampling ─────────────────────────────────────────────────────────
+
+/// Next-event estimation over a light list: a light is picked uniformly and
+/// sampled by solid angle, so the density is the average of the per-light
+/// densities.
+pub struct LightPdf<'a> {
+    pub lights: &'a [LightHandle],
+    pub origin: Point3,
+    /// Nearest hit that counts as reaching a light: `LIGHT_QUERY_T_MIN`
+    /// times the scene scale.
+    pub t_min: f64,
+}
+
+impl Pdf for LightPdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .lights
+            .iter()
+            .map(|light| light.pdf_value(self.origin, direction, self.t_min))
+            .sum();
+        sum / self.lights.len() as f64
+    }
+
+    fn generate(&self, rng: &mut dyn rand::RngCore) -> Vec3 {
+        let index = rng.gen_range(0..self.lights.len());
+        self.lights[index].random_toward(self.origin, rng)
+    }
+}
+
+// ─── Mixture ────────────────────────────────────────────────────────────────
+
+/// One-sample MIS over two strategies: `generate` picks `first` with
+/// probability `weight` and `second` otherwise, and `value` is the matching
+/// blend of both densities. Mixtures nest to combine more strategies.
+///
+/// A strategy with zero weight is never sampled or evaluated, so it need not
+/// be valid (e.g. an empty light list or an untrained guide).
+pub struct MixturePdf<'a> {
+    first: &'a dyn Pdf,
+    second: &'a dyn Pdf,
+    weight: f64,
+}
+
+impl<'a> MixturePdf<'a> {
+    pub fn new(first: &'a dyn Pdf, second: &'a dyn Pdf, weight: f64) -> Self {
+        Self {
+            first,
+            second,
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        let mut pdf = 0.0;
+        if self.weight > 0.0 {
+            pdf += self.weight * self.first.value(direction);
+        }
+        if self.weight < 1.0 {
+            pdf += (1.0 - self.weight) * self.second.value(direction);
+        }
+        pdf
+    }
+
+    fn generate(&self, rng: &mut dyn rand::RngCore) -> Vec3 {
+        if self.weight > 0.0 && rng.gen::<f64>() < self.weight {
+            self.first.generate(rng)
+        } else {
+            self.second.generate(rng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Emissive, Lambertian, Material, Quad, ThinTranslucent, LIGHT_QUERY_T_MIN};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use std::sync::Arc;
+
+    /// Monte Carlo estimate of ∫ pdf dω over the sphere from uniform
+    /// directions, whose density is 1 / 4π.
+    fn integrate(pdf: &dyn Pdf, rng: &mut SmallRng) -> f64 {
+        const SAMPLES: usize = 400_000;
+        let sum: f64 = (0..SAMPLES)
+            .map(|_| pdf.value(Vec3::random_unit_vector(rng)))
+            .sum();
+        sum / SAMPLES as f64 * 4.0 * std::f64::consts::PI
+    }
+
+    #[test]
+    fn mixture_pdf_integrates_to_one() {
+        let floor = Lambertian::new(Color::new(0.5, 0.5, 0.5));
+        let hit = HitRecord {
+            point: Point3::zero(),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            t: 1.0,
+            front_face: true,
+            material: &floor,
+            object: None,
+        };
+        let ray = Ray::new(Point3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0));
+        let brdf = ScatterPdf {
+            ray: &ray,
+            hit: &hit,
+            sampled: hit.normal,
+        };
+        let lights = [LightHandle::Area(Arc::new(Quad::new(
+            Point3::new(-0.5, 1.0, -0.5),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Emissive::new(Color::new(1.0, 1.0, 1.0), 4.0),
+        )))];
+        let light = LightPdf {
+            lights: &lights,
+            origin: hit.point,
+            t_min: LIGHT_QUERY_T_MIN,
+        };
+
+        let mut rng = SmallRng::seed_from_u64(1);
+        for weight in [0.0, 0.3, 0.5, 1.0] {
+            let mixture = MixturePdf::new(&light, &brdf, weight);
+            let integral = integrate(&mixture, &mut rng);
+            assert!(
+                (integral - 1.0).abs() < 0.02,
+                "weight {weight}: ∫ pdf dω = {integral}"
+            );
+        }
+    }
+
+    #[test]
+    fn thin_translucent_pdf_integrates_to_one() {
+        let leaf = ThinTranslucent::new(Color::new(0.12, 0.3, 0.08), Color::new(0.35, 0.7, 0.15));
+        let hit = HitRecord {
+            point: Point3::zero(),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            t: 1.0,
+            front_face: true,
+            material: &leaf,
+            object: None,
+        };
+        let ray = Ray::new(Point3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0));
+        let brdf = ScatterPdf {
+            ray: &ray,
+            hit: &hit,
+            sampled: hit.normal,
+        };
+        let mut rng = SmallRng::seed_from_u64(2);
+        let integral = integrate(&brdf, &mut rng);
+        assert!((integral - 1.0).abs() < 0.02, "∫ pdf dω = {integral}");
+
+        // The density below the sheet matches how often `scatter` transmits.
+        let below = |direction: Vec3| {
+            if direction.y < 0.0 {
+                brdf.value(direction)
+            } else {
+                0.0
+            }
+        };
+        let transmitted_mass = (0..100_000)
+            .map(|_| below(Vec3::random_unit_vector(&mut rng)))
+            .sum::<f64>()
+            / 100_000.0
+            * 4.0
+            * std::f64::consts::PI;
+        let transmitted = (0..100_000)
+            .filter(|_| {
+                let (scattered, _) = leaf.scatter(&ray, &hit, &mut rng).unwrap();
+                scattered.direction.y < 0.0
+            })
+            .count() as f64
+            / 100_000.0;
+        assert!(
+            (transmitted_mass - transmitted).abs() < 0.02,
+            "pdf mass {transmitted_mass} vs sampled {transmitted}"
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::camera::{Camera, CameraConfig};
 use crate::math::*;
-use crate::renderer::{RenderConfig, SkyModel};
+use crate::renderer::{RenderConfig, SkyModel, ToneMapOp};
 use crate::scene::*;
 use rand::Rng;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// A complete scene description bundling geometry, camera, lighting, and
 /// render settings. Scene presets allow users to quickly render showcase
@@ -12,6 +14,80 @@ pub struct SceneDescription {
     pub objects: Vec<Box<dyn Hittable>>,
     pub camera_config: CameraConfig,
     pub sky: SkyModel,
+    /// Recommended samples per pixel, bounce depth and tone mapping, used
+    /// unless overridden on the command line: a small scene converges in
+    /// far fewer samples than an enclosed, indirectly lit one.
+    pub samples_per_pixel: u32,
+    pub max_bounces: u32,
+    pub tone_map: ToneMapOp,
+}
+
+/// Composition of a scene's geometry, gathered from its objects before
+/// they are handed to the BVH.
+pub struct SceneStats {
+    pub primitives: BTreeMap<&'static str, usize>,
+    pub materials: BTreeMap<&'static str, usize>,
+    pub emitters: usize,
+    /// Summed area of the bounded primitives.
+    pub surface_area: f64,
+    /// Primitives with infinite area (planes), left out of the area and
+    /// bounds.
+    pub unbounded: usize,
+    /// Bounds of the bounded primitives, if there are any.
+    pub bounds: Option<Aabb>,
+}
+
+impl SceneDescription {
+    pub fn stats(&self) -> SceneStats {
+        let mut stats = SceneStats {
+            primitives: BTreeMap::new(),
+            materials: BTreeMap::new(),
+            emitters: 0,
+            surface_area: 0.0,
+            unbounded: 0,
+            bounds: None,
+        };
+        for object in &self.objects {
+            *stats.primitives.entry(object.kind()).or_default() += 1;
+            let material = object.material().map_or("(none)", |m| m.label());
+            *stats.materials.entry(material).or_default() += 1;
+            stats.emitters += usize::from(object.is_emitter());
+            let area = object.surface_area();
+            if area.is_finite() {
+                stats.surface_area += area;
+                let bbox = object.bounding_box();
+                stats.bounds = Some(stats.bounds.map_or(bbox, |b| Aabb::surrounding(&b, &bbox)));
+            } else {
+                stats.unbounded += 1;
+            }
+        }
+        stats
+    }
+}
+
+impl SceneStats {
+    pub fn print(&self) {
+        let counts = |map: &BTreeMap<&str, usize>| {
+            map.iter()
+                .map(|(name, n)| format!("{n} {name}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        eprintln!("  Primitives: {}", counts(&self.primitives));
+        eprintln!("  Materials:  {}", counts(&self.materials));
+        eprintln!("  Emitters:   {}", self.emitters);
+        let unbounded = match self.unbounded {
+            0 => String::new(),
+            n => format!(" (+{n} unbounded)"),
+        };
+        eprintln!("  Area:       {:.3}{unbounded}", self.surface_area);
+        if let Some(b) = self.bounds {
+            eprintln!(
+                "  Bounds:     ({:.2}, {:.2}, {:.2}) – ({:.2}, {:.2}, {:.2})",
+                b.min.x, b.min.y, b.min.z, b.max.x, b.max.y, b.max.z
+            );
+        }
+    }
 }
 
 /// Available built-in scene presets.
@@ -46,14 +122,17 @@ fn build_showcase() -> SceneDescription {
     let mut objects: Vec<Box<dyn Hittable>> = Vec::new();
     let mut rng = rand::thread_rng();
 
-    // Ground — checkerboard pattern
+    // Ground — glossy checkerboard tiles
     objects.push(Box::new(Sphere::new(
         Point3::new(0.0, -1000.0, 0.0),
         1000.0,
-        Checkerboard::new(
-            Color::new(0.05, 0.05, 0.05),
-            Color::new(0.95, 0.95, 0.95),
-            10.0,
+        Metal::new(
+            Checkerboard::new(
+                Color::new(0.05, 0.05, 0.05),
+                Color::new(0.95, 0.95, 0.95),
+                10.0,
+            ),
+            0.3,
         ),
     )));
 
@@ -107,7 +186,9 @@ fn build_showcase() -> SceneDescription {
                     rng.gen::<f64>() * rng.gen::<f64>(),
                     rng.gen::<f64>() * rng.gen::<f64>(),
                 );
-                Box::new(Sphere::new(center, 0.2, Lambertian::new(albedo)))
+                // Diffuse spheres bounce upward, blurred by an open shutter.
+                let rise = Vec3::new(0.0, rng.gen_range(0.0..0.5), 0.0);
+                Box::new(Sphere::new(center, 0.2, Lambertian::new(albedo)).with_velocity(rise))
             } else if choose_mat < 0.9 {
                 let albedo = Color::new(
                     rng.gen_range(0.5..1.0),
@@ -139,6 +220,9 @@ fn build_showcase() -> SceneDescription {
             horizon: Color::new(1.0, 1.0, 1.0),
             zenith: Color::new(0.5, 0.7, 1.0),
         },
+        samples_per_pixel: 32,
+        max_bounces: 12,
+        tone_map: ToneMapOp::None,
     }
 }
 
@@ -151,65 +235,85 @@ fn build_cornell() -> SceneDescription {
 
     // Cornell box walls using Quad primitives for proper finite geometry
     // Floor
-    objects.push(Box::new(Quad::new(
-        Point3::new(-2.0, 0.0, -4.0),
-        Vec3::new(4.0, 0.0, 0.0),
-        Vec3::new(0.0, 0.0, 4.0),
-        Lambertian::new(white),
+    objects.push(Box::new(Named::new(
+        "floor",
+        Quad::new(
+            Point3::new(-2.0, 0.0, -4.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            Lambertian::new(white),
+        ),
     )));
 
     // Ceiling
-    objects.push(Box::new(Quad::new(
-        Point3::new(-2.0, 4.0, -4.0),
-        Vec3::new(4.0, 0.0, 0.0),
-        Vec3::new(0.0, 0.0, 4.0),
-        Lambertian::new(white),
+    objects.push(Box::new(Named::new(
+        "ceiling",
+        Quad::new(
+            Point3::new(-2.0, 4.0, -4.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            Lambertian::new(white),
+        ),
     )));
 
     // Back wall
-    objects.push(Box::new(Quad::new(
-        Point3::new(-2.0, 0.0, -4.0),
-        Vec3::new(4.0, 0.0, 0.0),
-        Vec3::new(0.0, 4.0, 0.0),
-        Lambertian::new(white),
+    objects.push(Box::new(Named::new(
+        "back wall",
+        Quad::new(
+            Point3::new(-2.0, 0.0, -4.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            Lambertian::new(white),
+        ),
     )));
 
     // Left wall (red)
-    objects.push(Box::new(Quad::new(
-        Point3::new(-2.0, 0.0, -4.0),
-        Vec3::new(0.0, 0.0, 4.0),
-        Vec3::new(0.0, 4.0, 0.0),
-        Lambertian::new(red),
+    objects.push(Box::new(Named::new(
+        "left wall",
+        Quad::new(
+            Point3::new(-2.0, 0.0, -4.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            Lambertian::new(red),
+        ),
     )));
 
     // Right wall (green)
-    objects.push(Box::new(Quad::new(
-        Point3::new(2.0, 0.0, -4.0),
-        Vec3::new(0.0, 0.0, 4.0),
-        Vec3::new(0.0, 4.0, 0.0),
-        Lambertian::new(green),
+    objects.push(Box::new(Named::new(
+        "right wall",
+        Quad::new(
+            Point3::new(2.0, 0.0, -4.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            Lambertian::new(green),
+        ),
     )));
 
     // Area light on ceiling (small bright quad)
-    objects.push(Box::new(Quad::new(
-        Point3::new(-0.5, 3.99, -2.5),
-        Vec3::new(1.0, 0.0, 0.0),
-        Vec3::new(0.0, 0.0, 1.0),
-        Emissive::new(Color::new(1.0, 0.95, 0.85), 18.0),
+    objects.push(Box::new(Named::new(
+        "ceiling light",
+        Quad::new(
+            Point3::new(-0.5, 3.99, -2.5),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Emissive::new(Color::new(1.0, 0.95, 0.85), 18.0),
+        ),
     )));
 
     // Metal sphere (left)
-    objects.push(Box::new(Sphere::new(
-        Point3::new(-0.7, 0.6, -2.2),
-        0.6,
-        Metal::new(Color::new(0.9, 0.9, 0.95), 0.02),
+    objects.push(Box::new(Named::new(
+        "metal sphere",
+        Sphere::new(
+            Point3::new(-0.7, 0.6, -2.2),
+            0.6,
+            Metal::new(Color::new(0.9, 0.9, 0.95), 0.02),
+        ),
     )));
 
     // Glass sphere (right)
-    objects.push(Box::new(Sphere::new(
-        Point3::new(0.7, 0.45, -1.5),
-        0.45,
-        Dielectric::new(1.5),
+    objects.push(Box::new(Named::new(
+        "glass sphere",
+        Sphere::new(Point3::new(0.7, 0.45, -1.5), 0.45, Dielectric::new(1.5)),
     )));
 
     SceneDescription {
@@ -225,6 +329,9 @@ fn build_cornell() -> SceneDescription {
             focus_dist: 5.0,
         },
         sky: SkyModel::Black,
+        samples_per_pixel: 256,
+        max_bounces: 16,
+        tone_map: ToneMapOp::Aces,
     }
 }
 
@@ -275,6 +382,9 @@ fn build_minimal() -> SceneDescription {
             horizon: Color::new(1.0, 1.0, 1.0),
             zenith: Color::new(0.3, 0.5, 1.0),
         },
+        samples_per_pixel: 16,
+        max_bounces: 8,
+        tone_map: ToneMapOp::None,
     }
 }
 
@@ -286,93 +396,143 @@ fn build_gallery() -> SceneDescription {
     let mut objects: Vec<Box<dyn Hittable>> = Vec::new();
 
     // Ground — large checkerboard plane
-    objects.push(Box::new(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        Checkerboard::new(
-            Color::new(0.08, 0.08, 0.12),
-            Color::new(0.85, 0.85, 0.80),
-            8.0,
+    objects.push(Box::new(Named::new(
+        "ground",
+        Sphere::new(
+            Point3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            Checkerboard::new(
+                Color::new(0.08, 0.08, 0.12),
+                Color::new(0.85, 0.85, 0.80),
+                8.0,
+            ),
         ),
     )));
 
     // Backdrop quad — a large matte panel behind the scene
-    objects.push(Box::new(Quad::new(
-        Point3::new(-6.0, 0.0, -5.0),
-        Vec3::new(12.0, 0.0, 0.0),
-        Vec3::new(0.0, 6.0, 0.0),
-        Lambertian::new(Color::new(0.15, 0.15, 0.2)),
+    objects.push(Box::new(Named::new(
+        "backdrop",
+        Quad::new(
+            Point3::new(-6.0, 0.0, -5.0),
+            Vec3::new(12.0, 0.0, 0.0),
+            Vec3::new(0.0, 6.0, 0.0),
+            Lambertian::new(Color::new(0.15, 0.15, 0.2)),
+        ),
     )));
 
-    // Disk pedestal — a reflective circular platform
-    objects.push(Box::new(Disk::new(
-        Point3::new(0.0, 0.01, -1.0),
-        Vec3::unit_y(),
-        2.5,
-        Metal::new(Color::new(0.7, 0.7, 0.75), 0.15),
+    // Disk pedestal — a glossy tiled circular platform
+    objects.push(Box::new(Named::new(
+        "pedestal",
+        Disk::new(
+            Point3::new(0.0, 0.01, -1.0),
+            Vec3::unit_y(),
+            2.5,
+            Metal::new(
+                Triplanar::new(
+                    TilePattern {
+                        tile: Color::new(0.7, 0.7, 0.75),
+                        grout: Color::new(0.2, 0.2, 0.22),
+                        grout_width: 0.06,
+                    },
+                    0.5,
+                ),
+                0.15,
+            ),
+        ),
     )));
 
     // Center: large glass sphere with inner bubble
-    objects.push(Box::new(Sphere::new(
-        Point3::new(0.0, 1.0, -1.0),
-        1.0,
-        Dielectric::new(1.5),
+    objects.push(Box::new(Named::new(
+        "glass sphere",
+        Sphere::new(Point3::new(0.0, 1.0, -1.0), 1.0, Dielectric::new(1.5)),
     )));
-    objects.push(Box::new(Sphere::new(
-        Point3::new(0.0, 1.0, -1.0),
-        -0.92,
-        Dielectric::new(1.5),
+    objects.push(Box::new(Named::new(
+        "glass bubble",
+        Sphere::new(Point3::new(0.0, 1.0, -1.0), -0.92, Dielectric::new(1.5)),
     )));
 
     // Left: gradient material sphere (warm tones)
-    objects.push(Box::new(Sphere::new(
-        Point3::new(-2.8, 0.7, -0.5),
-        0.7,
-        GradientMaterial::new(
-            Color::new(0.95, 0.3, 0.1),
-            Color::new(0.95, 0.85, 0.2),
-            Vec3::unit_y(),
+    objects.push(Box::new(Named::new(
+        "gradient sphere",
+        Sphere::new(
+            Point3::new(-2.8, 0.7, -0.5),
+            0.7,
+            GradientMaterial::new(
+                Color::new(0.95, 0.3, 0.1),
+                Color::new(0.95, 0.85, 0.2),
+                Vec3::unit_y(),
+            ),
         ),
     )));
 
     // Right: brushed metal sphere
-    objects.push(Box::new(Sphere::new(
-        Point3::new(2.8, 0.8, -0.8),
-        0.8,
-        Metal::new(Color::new(0.9, 0.75, 0.6), 0.08),
+    objects.push(Box::new(Named::new(
+        "brushed metal sphere",
+        Sphere::new(
+            Point3::new(2.8, 0.8, -0.8),
+            0.8,
+            Metal::new(Color::new(0.9, 0.75, 0.6), 0.08),
+        ),
     )));
 
     // Small accent spheres
-    objects.push(Box::new(Sphere::new(
-        Point3::new(-1.2, 0.3, 0.8),
-        0.3,
-        Lambertian::new(Color::new(0.1, 0.4, 0.85)),
+    objects.push(Box::new(Named::new(
+        "blue sphere",
+        Sphere::new(
+            Point3::new(-1.2, 0.3, 0.8),
+            0.3,
+            Lambertian::new(Color::new(0.1, 0.4, 0.85)),
+        ),
     )));
 
-    objects.push(Box::new(Sphere::new(
-        Point3::new(1.5, 0.25, 1.0),
-        0.25,
-        Metal::new(Color::new(0.95, 0.95, 0.95), 0.0),
+    objects.push(Box::new(Named::new(
+        "chrome sphere",
+        Sphere::new(
+            Point3::new(1.5, 0.25, 1.0),
+            0.25,
+            Metal::new(Color::new(0.95, 0.95, 0.95), 0.0),
+        ),
     )));
 
-    objects.push(Box::new(Sphere::new(
-        Point3::new(0.8, 0.2, 0.5),
-        0.2,
-        Lambertian::new(Color::new(0.8, 0.15, 0.5)),
+    objects.push(Box::new(Named::new(
+        "magenta sphere",
+        Sphere::new(
+            Point3::new(0.8, 0.2, 0.5),
+            0.2,
+            Lambertian::new(Color::new(0.8, 0.15, 0.5)),
+        ),
     )));
 
     // Floating emissive sphere (warm light source)
-    objects.push(Box::new(Sphere::new(
-        Point3::new(-1.0, 3.5, -2.0),
-        0.3,
-        Emissive::new(Color::new(1.0, 0.9, 0.7), 12.0),
+    objects.push(Box::new(Named::new(
+        "warm light",
+        Sphere::new(
+            Point3::new(-1.0, 3.5, -2.0),
+            0.3,
+            Emissive::new(Color::new(1.0, 0.9, 0.7), 12.0),
+        ),
     )));
 
     // Cool accent light
-    objects.push(Box::new(Sphere::new(
-        Point3::new(2.0, 2.5, 0.0),
-        0.2,
-        Emissive::new(Color::new(0.5, 0.7, 1.0), 10.0),
+    objects.push(Box::new(Named::new(
+        "cool light",
+        Sphere::new(
+            Point3::new(2.0, 2.5, 0.0),
+            0.2,
+            Emissive::new(Color::new(0.5, 0.7, 1.0), 10.0),
+        ),
+    )));
+
+    // Translucent leaf in front of the accent light — glows where the
+    // light behind it is transmitted through the sheet
+    objects.push(Box::new(Named::new(
+        "leaf",
+        Quad::new(
+            Point3::new(1.6, 2.05, 0.45),
+            Vec3::new(0.8, 0.0, 0.0),
+            Vec3::new(0.0, 0.8, 0.0),
+            ThinTranslucent::new(Color::new(0.12, 0.3, 0.08), Color::new(0.35, 0.7, 0.15)),
+        ),
     )));
 
     SceneDescription {
@@ -391,6 +551,9 @@ fn build_gallery() -> SceneDescription {
             horizon: Color::new(0.15, 0.15, 0.2),
             zenith: Color::new(0.02, 0.02, 0.08),
         },
+        samples_per_pixel: 64,
+        max_bounces: 12,
+        tone_map: ToneMapOp::Reinhard,
     }
 }
 
@@ -438,23 +601,60 @@ fn build_stress() -> SceneDescription {
             horizon: Color::new(1.0, 0.95, 0.88),
             zenith: Color::new(0.4, 0.6, 1.0),
         },
+        samples_per_pixel: 16,
+        max_bounces: 8,
+        tone_map: ToneMapOp::None,
     }
 }
 
 /// Constructs the final renderable world from a scene description by
-/// building a BVH over all objects for accelerated ray queries.
-pub fn build_world(mut desc: SceneDescription) -> (BvhNode, Camera, SkyModel, RenderConfig) {
+/// building a BVH over all objects for accelerated ray queries. Emissive
+/// primitives are shared between the BVH and the returned light list.
+/// Leaves hold up to `max_leaf_size` primitives.
+pub fn build_world(
+    mut desc: SceneDescription,
+    max_leaf_size: usize,
+) -> (BvhNode, Camera, SkyModel, RenderConfig, Vec<LightHandle>) {
     let camera = Camera::new(&desc.camera_config);
     let aspect = desc.camera_config.aspect_ratio;
 
-    let objects: Vec<Box<dyn Hittable>> = desc.objects.drain(..).collect();
-    let bvh = BvhNode::build(objects);
+    let mut lights = Vec::new();
+    let objects: Vec<Box<dyn Hittable>> = desc
+        .objects
+        .drain(..)
+        .map(|object| {
+            if !object.is_emitter() {
+                return object;
+            }
+            let shared: Arc<dyn Hittable> = Arc::from(object);
+            lights.push(LightHandle::Area(Arc::clone(&shared)));
+            Box::new(shared) as Box<dyn Hittable>
+        })
+        .collect();
+    let bvh = BvhNode::build(objects, max_leaf_size);
 
     let config = RenderConfig {
         width: (80.0 * aspect) as u32,
         height: 80,
+        samples_per_pixel: desc.samples_per_pixel,
+        max_bounces: desc.max_bounces,
+        tone_map: desc.tone_map,
+        max_leaf_size,
         ..Default::default()
     };
 
-    (bvh, camera, desc.sky, config)
+    (bvh, camera, desc.sky, config, lights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cornell_recommends_its_quality_settings() {
+        let (_, _, _, config, _) = build_world(ScenePreset::Cornell.build(), 1);
+        assert_eq!(config.samples_per_pixel, 256);
+        assert_eq!(config.max_bounces, 16);
+        assert_eq!(config.tone_map, ToneMapOp::Aces);
+    }
 }
@@ -1,13 +1,21 @@
 use crate::camera::Camera;
+use crate::irradiance::{IrradianceCache, ShRadiance};
 use crate::math::*;
+use crate::pdf::{LightPdf, MixturePdf, Pdf, ScatterPdf};
+use crate::sampler::{Sampler, SamplerKind};
 use crate::scene::*;
 use crossterm::style::{self, Stylize};
+use crossterm::{cursor, terminal};
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
 use std::io::{self, Write};
+use std::sync::Mutex;
 
 // ─── Render Configuration ───────────────────────────────────────────────────
 
+/// Ray epsilon for scenes of roughly ten units across, like the presets.
+pub const DEFAULT_RAY_EPSILON: f64 = 0.001;
+
 pub struct RenderConfig {
     pub width: u32,
     pub height: u32,
@@ -16,6 +24,77 @@ pub struct RenderConfig {
     pub output_mode: OutputMode,
     pub gamma: bool,
     pub tone_map: ToneMapOp,
+    /// Drive braille/ASCII structure from the log luminance of the linear
+    /// HDR buffer rather than from the tone-mapped display image.
+    pub log_luminance: bool,
+    /// Locks the rendered image to this width/height ratio, padding the rest
+    /// of the framebuffer with black bars instead of stretching the image.
+    pub frame_aspect: Option<f64>,
+    /// Displayed width/height of one framebuffer pixel. 1.0 treats pixels as
+    /// square; `--pixel-ar` derives it from the terminal's cell shape.
+    pub pixel_aspect: f64,
+    /// Fixed RNG seed. `None` seeds from OS entropy; distributed partial
+    /// renders set it so each sample range draws an independent,
+    /// reproducible sequence.
+    pub seed: Option<u64>,
+    /// Global index of the first sample, nonzero for a `--sample-range`
+    /// partial. Pixel streams are seeded from `seed + first_sample`, and
+    /// low-discrepancy samplers continue their sequence from this index.
+    pub first_sample: u32,
+    /// Mirror the finished image left↔right before display and export.
+    pub flip_horizontal: bool,
+    /// Mirror the finished image top↔bottom before display and export.
+    pub flip_vertical: bool,
+    /// Composition overlays drawn over the displayed image.
+    pub guides: Vec<Guide>,
+    /// Learn a per-pixel distribution of bright incoming directions at the
+    /// primary hit and steer part of the first bounce toward it.
+    pub guiding: bool,
+    /// Shade every non-emissive surface with a matte gray Lambertian, leaving
+    /// the scene itself untouched, to judge form and lighting alone.
+    pub clay: bool,
+    /// Display-referred contrast around mid-gray; 1.0 leaves the image as is.
+    pub contrast: f64,
+    /// Saturation scale around each pixel's luminance; 0.0 is grayscale.
+    pub saturation: f64,
+    /// Convert the developed, display-referred image to its luminance, for
+    /// terminal and file output alike.
+    pub mono: bool,
+    /// Linear multiplier applied to the radiance before tone mapping.
+    pub exposure: f64,
+    /// Tint surfaces near the camera's focus plane green to preview depth
+    /// of field.
+    pub focus_preview: bool,
+    /// Bake a spherical-harmonic irradiance cache before rendering and end
+    /// diffuse paths on it after the first bounce. A biased preview.
+    pub sh_cache: bool,
+    /// Minimum hit distance along traced rays, which keeps a bounce from
+    /// re-hitting the surface it left (shadow acne). Scales with the scene
+    /// size through `--scene-scale`.
+    pub ray_epsilon: f64,
+    /// Trace one pixel per `downscale`×`downscale` block and repeat it over
+    /// the block, for fast previews. 1 renders at full resolution.
+    pub downscale: u32,
+    /// Row-major 3×3 matrix applied to the display-referred RGB sent to the
+    /// terminal, correcting for how it renders color. File export is
+    /// unaffected; `None` is the identity.
+    pub display_matrix: Option<[[f64; 3]; 3]>,
+    /// Most primitives per BVH leaf the world was built with. Leaves are
+    /// tested linearly, so this trades box tests for primitive tests; the
+    /// image is the same for any size.
+    pub max_leaf_size: usize,
+    /// Source of the per-sample random numbers: pseudorandom, or a
+    /// randomized low-discrepancy sequence.
+    pub sampler: SamplerKind,
+    /// Worker threads used to render; 1 renders serially.
+    pub threads: usize,
+    /// Cap on throughput in millions of samples per second. Workers sleep
+    /// between rows to stay under it; `None` renders at full speed.
+    pub throttle: Option<f64>,
+    /// Radiance returned when a camera ray escapes the scene. The sky still
+    /// lights the scene and shows in reflections; only its direct view is
+    /// replaced.
+    pub background: Option<Color>,
 }
 
 impl Default for RenderConfig {
@@ -28,10 +107,122 @@ impl Default for RenderConfig {
             output_mode: OutputMode::TrueColor,
             gamma: true,
             tone_map: ToneMapOp::None,
+            log_luminance: false,
+            frame_aspect: None,
+            pixel_aspect: 1.0,
+            seed: None,
+            first_sample: 0,
+            flip_horizontal: false,
+            flip_vertical: false,
+            guides: Vec::new(),
+            guiding: false,
+            clay: false,
+            contrast: 1.0,
+            saturation: 1.0,
+            mono: false,
+            exposure: 1.0,
+            background: None,
+            max_leaf_size: 1,
+            sampler: SamplerKind::Random,
+            threads: 1,
+            throttle: None,
+            focus_preview: false,
+            sh_cache: false,
+            ray_epsilon: DEFAULT_RAY_EPSILON,
+            downscale: 1,
+            display_matrix: None,
         }
     }
 }
 
+impl RenderConfig {
+    /// Scene size relative to the presets, as set through `ray_epsilon`.
+    pub fn scene_scale(&self) -> f64 {
+        self.ray_epsilon / DEFAULT_RAY_EPSILON
+    }
+
+    /// Width/height of the whole framebuffer as it appears on screen,
+    /// accounting for non-square pixels.
+    pub fn display_aspect(&self) -> f64 {
+        self.width as f64 * self.pixel_aspect / self.height as f64
+    }
+
+    /// The active image rectangle `(x, y, width, height)` inside the
+    /// framebuffer. Without a locked frame aspect this is the full buffer;
+    /// otherwise the largest centered rectangle that *displays* at that
+    /// aspect, leaving letterbox (top/bottom) or pillarbox (left/right) bars
+    /// around it.
+    pub fn frame_rect(&self) -> (u32, u32, u32, u32) {
+        let (w, h) = (self.width, self.height);
+        let Some(aspect) = self.frame_aspect else {
+            return (0, 0, w, h);
+        };
+        if self.display_aspect() > aspect {
+            let inner_w = ((h as f64 * aspect / self.pixel_aspect).round() as u32).clamp(1, w);
+            ((w - inner_w) / 2, 0, inner_w, h)
+        } else {
+            let inner_h = ((w as f64 * self.pixel_aspect / aspect).round() as u32).clamp(1, h);
+            (0, (h - inner_h) / 2, w, inner_h)
+        }
+    }
+
+    /// Checks the settings before rendering, so bad input is reported as an
+    /// actionable message instead of a panic or an empty image.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 {
+            return Err("width must be > 0".into());
+        }
+        if self.height == 0 {
+            return Err("height must be > 0".into());
+        }
+        if self.samples_per_pixel == 0 {
+            // Zero samples would average to NaN in every pixel.
+            return Err("spp must be ≥ 1".into());
+        }
+        if self.max_bounces == 0 {
+            return Err("bounces must be ≥ 1".into());
+        }
+        if self.threads == 0 {
+            return Err("threads must be ≥ 1".into());
+        }
+        if self.downscale == 0 {
+            return Err("preview downscale must be ≥ 1".into());
+        }
+        if !(self.ray_epsilon.is_finite() && self.ray_epsilon > 0.0) {
+            return Err("scene scale must be positive".into());
+        }
+        if let Some(cap) = self.throttle {
+            if !(cap.is_finite() && cap > 0.0) {
+                return Err("throttle must be a positive Mrays/s rate".into());
+            }
+        }
+        let (cols, rows) = self.output_mode.pixels_per_cell();
+        if self.width < cols || self.height < rows {
+            let mode = match self.output_mode {
+                OutputMode::Braille => "braille",
+                OutputMode::HalfBlock => "half-block",
+                OutputMode::TrueColor => "true-color",
+                OutputMode::Ascii => "ASCII",
+            };
+            return Err(format!(
+                "{mode} mode needs width ≥ {cols} and height ≥ {rows}"
+            ));
+        }
+        if !(self.pixel_aspect.is_finite() && self.pixel_aspect > 0.0) {
+            return Err("pixel aspect must be positive".into());
+        }
+        // Pixel centers are spread over (w − 1) and (h − 1) steps.
+        let (_, _, w, h) = self.frame_rect();
+        if w < 2 || h < 2 {
+            return Err(format!(
+                "the {w}×{h} image inside the frame is too small; \
+                 it needs at least 2×2 pixels"
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Terminal output encoding modes with varying resolution and color support.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputMode {
@@ -45,6 +236,55 @@ pub enum OutputMode {
     Ascii,
 }
 
+impl OutputMode {
+    /// Framebuffer pixels packed into one terminal cell, as (columns, rows).
+    pub fn pixels_per_cell(self) -> (u32, u32) {
+        match self {
+            OutputMode::Braille => (2, 4),
+            OutputMode::TrueColor | OutputMode::Ascii => (1, 1),
+            OutputMode::HalfBlock => (1, 2),
+        }
+    }
+
+    /// Converts a terminal cell aspect (width/height, e.g. 0.5 for the
+    /// common 1:2 cell) into the displayed aspect of one framebuffer pixel.
+    pub fn pixel_aspect(self, cell_aspect: f64) -> f64 {
+        let (cols, rows) = self.pixels_per_cell();
+        cell_aspect * rows as f64 / cols as f64
+    }
+
+    /// Picks the richest mode the attached terminal can show, along with the
+    /// reason. Detection is conservative: colored half-blocks only when the
+    /// terminal is known or advertised (`$COLORTERM`) to handle 24-bit
+    /// color, and the colorless ASCII ramp otherwise, including when stdout
+    /// is not a terminal at all.
+    pub fn detect() -> (Self, &'static str) {
+        use std::io::IsTerminal;
+
+        if !io::stdout().is_terminal() {
+            return (OutputMode::Ascii, "stdout is not a terminal");
+        }
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        if term.is_empty() || term == "dumb" {
+            return (OutputMode::Ascii, "no capable $TERM");
+        }
+        if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit") {
+            return (OutputMode::HalfBlock, "$COLORTERM advertises 24-bit color");
+        }
+        let known_truecolor = term == "xterm-kitty"
+            || std::env::var_os("WT_SESSION").is_some()
+            || matches!(
+                var("TERM_PROGRAM").as_str(),
+                "iTerm.app" | "WezTerm" | "vscode"
+            );
+        if known_truecolor {
+            return (OutputMode::HalfBlock, "known 24-bit color terminal");
+        }
+        (OutputMode::Ascii, "24-bit color not advertised")
+    }
+}
+
 // ─── Tone Mapping Operators ─────────────────────────────────────────────────
 
 /// Tone mapping operators for HDR → LDR conversion. These compress the
@@ -99,8 +339,120 @@ impl ToneMapOp {
     }
 }
 
+// ─── Resampling ─────────────────────────────────────────────────────────────
+
+/// Reconstruction filter for [`Framebuffer::resample`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleFilter {
+    /// Area average: each output pixel is the mean of the source pixels it
+    /// covers, weighted by overlap. Alias-free when shrinking.
+    Box,
+    /// Linear interpolation between the two nearest source pixels on each
+    /// axis. Smooth when enlarging.
+    Bilinear,
+}
+
+/// Source pixels and weights for each of `dst` output pixels along an axis
+/// of `src` pixels. Each output's weights sum to one.
+fn resample_taps(src: u32, dst: u32, filter: ResampleFilter) -> Vec<Vec<(usize, f64)>> {
+    let scale = f64::from(src) / f64::from(dst);
+    let last = src as usize - 1;
+    (0..dst)
+        .map(|i| match filter {
+            ResampleFilter::Box => {
+                let (start, end) = (f64::from(i) * scale, f64::from(i + 1) * scale);
+                let first = (start.floor() as usize).min(last);
+                let past = (end.ceil() as usize).clamp(first + 1, src as usize);
+                (first..past)
+                    .map(|j| {
+                        let overlap = end.min(j as f64 + 1.0) - start.max(j as f64);
+                        (j, overlap / scale)
+                    })
+                    .filter(|&(_, wt)| wt > 0.0)
+                    .collect()
+            }
+            ResampleFilter::Bilinear => {
+                let at = ((f64::from(i) + 0.5) * scale - 0.5).clamp(0.0, last as f64);
+                let j = at.floor() as usize;
+                let f = at - j as f64;
+                vec![(j, 1.0 - f), ((j + 1).min(last), f)]
+            }
+        })
+        .collect()
+}
+
+// ─── Stereo ─────────────────────────────────────────────────────────────────
+
+/// How the two eyes of a stereo pair are combined into one image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stereo {
+    /// Red channel from the left eye, green and blue from the right, for
+    /// red/cyan glasses.
+    Anaglyph,
+    /// Left eye on the left, for parallel (wall-eyed) viewing.
+    SideBySide,
+    /// Right eye on the left, for cross-eyed viewing.
+    Cross,
+}
+
+impl Framebuffer {
+    /// Combines two linear renders of the same size into a stereo image.
+    /// Side-by-side layouts are twice as wide as either eye.
+    pub fn stereo(left: &Framebuffer, right: &Framebuffer, mode: Stereo) -> Framebuffer {
+        let (w, h) = (left.width as usize, left.height as usize);
+        match mode {
+            Stereo::Anaglyph => Framebuffer {
+                width: left.width,
+                height: left.height,
+                pixels: left
+                    .pixels
+                    .iter()
+                    .zip(&right.pixels)
+                    .map(|(l, r)| Color::new(l.x, r.y, r.z))
+                    .collect(),
+            },
+            Stereo::SideBySide | Stereo::Cross => {
+                let (first, second) = if mode == Stereo::Cross {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+                let mut pixels = Vec::with_capacity(2 * w * h);
+                for y in 0..h {
+                    pixels.extend_from_slice(&first.pixels[y * w..(y + 1) * w]);
+                    pixels.extend_from_slice(&second.pixels[y * w..(y + 1) * w]);
+                }
+                Framebuffer {
+                    width: 2 * left.width,
+                    height: left.height,
+                    pixels,
+                }
+            }
+        }
+    }
+}
+
+// ─── Composition Guides ─────────────────────────────────────────────────────
+
+/// Composition overlay drawn over the finished image to help framing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Guide {
+    /// Lines dividing the frame into thirds both ways.
+    Thirds,
+    /// A small cross at the frame center.
+    Center,
+    /// Title-safe border, inset 10% from each edge.
+    Safe,
+}
+
+/// Color the guide lines are blended toward.
+const GUIDE_COLOR: Color = Color::new(1.0, 1.0, 1.0);
+/// Opacity of the guide lines.
+const GUIDE_OPACITY: f64 = 0.5;
+
 // ─── Framebuffer ────────────────────────────────────────────────────────────
 
+#[derive(Clone)]
 pub struct Framebuffer {
     pub width: u32,
     pub height: u32,
@@ -126,25 +478,391 @@ impl Framebuffer {
         self.pixels[(y * self.width + x) as usize]
     }
 
+    /// Mirrors the image in place horizontally and/or vertically. A pure
+    /// index remap, so it commutes with every per-pixel post-process step.
+    pub fn flip(&mut self, horizontal: bool, vertical: bool) {
+        let w = self.width as usize;
+        if horizontal {
+            for row in self.pixels.chunks_exact_mut(w) {
+                row.reverse();
+            }
+        }
+        if vertical {
+            let h = self.height as usize;
+            for y in 0..h / 2 {
+                let (top, bottom) = self.pixels.split_at_mut((h - 1 - y) * w);
+                top[y * w..(y + 1) * w].swap_with_slice(&mut bottom[..w]);
+            }
+        }
+    }
+
+    /// Resizes the image to `width`×`height` with `filter`, in linear space,
+    /// one axis at a time. Edge samples are clamped to the border pixels.
+    pub fn resample(&self, width: u32, height: u32, filter: ResampleFilter) -> Framebuffer {
+        let (w, h) = (self.width as usize, self.height as usize);
+        let taps_x = resample_taps(self.width, width, filter);
+        let taps_y = resample_taps(self.height, height, filter);
+        let weighted = |taps: &[(usize, f64)], at: &dyn Fn(usize) -> Color| {
+            taps.iter()
+                .fold(Color::zero(), |sum, &(i, wt)| sum + at(i) * wt)
+        };
+
+        let mut rows = Vec::with_capacity(width as usize * h);
+        for y in 0..h {
+            let row = &self.pixels[y * w..(y + 1) * w];
+            rows.extend(taps_x.iter().map(|taps| weighted(taps, &|x| row[x])));
+        }
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for taps in &taps_y {
+            for x in 0..width as usize {
+                pixels.push(weighted(taps, &|y| rows[y * width as usize + x]));
+            }
+        }
+        Framebuffer {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Blends `config.guides` over the image inside `config.frame_rect()`
+    /// as semi-transparent one-pixel lines. Expects a display-referred image.
+    pub fn draw_guides(&mut self, config: &RenderConfig) {
+        let (x0, y0, w, h) = config.frame_rect();
+        let at = |origin: u32, extent: u32, fraction: f64| {
+            origin + ((extent - 1) as f64 * fraction).round() as u32
+        };
+
+        // Mark first, so crossing lines are not blended twice.
+        let mut mask = vec![false; self.pixels.len()];
+        let stride = self.width as usize;
+        let hline = |mask: &mut [bool], y: u32, from: u32, to: u32| {
+            (from..=to).for_each(|x| mask[y as usize * stride + x as usize] = true)
+        };
+        let vline = |mask: &mut [bool], x: u32, from: u32, to: u32| {
+            (from..=to).for_each(|y| mask[y as usize * stride + x as usize] = true)
+        };
+        let (right, bottom) = (x0 + w - 1, y0 + h - 1);
+        for guide in &config.guides {
+            match guide {
+                Guide::Thirds => {
+                    for t in [1.0 / 3.0, 2.0 / 3.0] {
+                        hline(&mut mask, at(y0, h, t), x0, right);
+                        vline(&mut mask, at(x0, w, t), y0, bottom);
+                    }
+                }
+                Guide::Center => {
+                    let (cx, cy) = (at(x0, w, 0.5), at(y0, h, 0.5));
+                    let (arm_x, arm_y) = ((w / 20).max(1), (h / 20).max(1));
+                    hline(
+                        &mut mask,
+                        cy,
+                        cx.saturating_sub(arm_x).max(x0),
+                        (cx + arm_x).min(right),
+                    );
+                    vline(
+                        &mut mask,
+                        cx,
+                        cy.saturating_sub(arm_y).max(y0),
+                        (cy + arm_y).min(bottom),
+                    );
+                }
+                Guide::Safe => {
+                    let (left, right) = (at(x0, w, 0.1), at(x0, w, 0.9));
+                    let (top, bottom) = (at(y0, h, 0.1), at(y0, h, 0.9));
+                    hline(&mut mask, top, left, right);
+                    hline(&mut mask, bottom, left, right);
+                    vline(&mut mask, left, top, bottom);
+                    vline(&mut mask, right, top, bottom);
+                }
+            }
+        }
+
+        for (pixel, _) in self.pixels.iter_mut().zip(mask).filter(|(_, m)| *m) {
+            *pixel = pixel.lerp(GUIDE_COLOR, GUIDE_OPACITY);
+        }
+    }
+
+    /// Converts a linear HDR radiance buffer into a display-referred image:
+    /// exposure and tone mapping in linear space, then contrast/saturation
+    /// grading, then optional gamma correction.
+    pub fn post_process(&self, config: &RenderConfig) -> Framebuffer {
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|&c| {
+                let mapped = grade(
+                    config.tone_map.apply(c * config.exposure),
+                    config.contrast,
+                    config.saturation,
+                );
+                let display = if config.gamma {
+                    mapped.gamma_correct()
+                } else {
+                    mapped
+                };
+                if config.mono {
+                    Color::ones() * display.luminance()
+                } else {
+                    display
+                }
+            })
+            .collect();
+        Framebuffer {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+
+    /// Exposure that maps the log-average luminance of the image inside
+    /// `config.frame_rect()` to mid-gray (Reinhard's key value):
+    ///   exposure = 0.18 / exp(mean(ln(δ + L)))
+    /// δ keeps black pixels from sending the log average to −∞. An image
+    /// wider than `config.width` is a side-by-side stereo pair, metered over
+    /// the frame of every eye.
+    pub fn auto_exposure(&self, config: &RenderConfig) -> f64 {
+        const DELTA: f64 = 1e-4;
+        let (x0, y0, w, h) = config.frame_rect();
+        let eyes = (self.width / config.width).max(1);
+        let mut log_sum = 0.0;
+        for eye in 0..eyes {
+            let x0 = x0 + eye * config.width;
+            for y in y0..y0 + h {
+                for x in x0..x0 + w {
+                    log_sum += (DELTA + self.get(x, y).luminance().max(0.0)).ln();
+                }
+            }
+        }
+        let log_average = (log_sum / f64::from(eyes * w * h)).exp();
+        MID_GRAY / log_average
+    }
+
+    /// Quantizes the display-referred pixels to 8-bit RGB.
+    ///
+    /// With `dither`, Floyd–Steinberg error diffusion carries each pixel's
+    /// rounding error into its unvisited neighbours (7/16 right, 3/16
+    /// below-left, 5/16 below, 1/16 below-right), so smooth gradients such as
+    /// the sky keep their average level instead of collapsing into contour
+    /// bands. The scan order is fixed, so the result is fully deterministic.
+    pub fn quantize(&self, dither: bool) -> Vec<[u8; 3]> {
+        // Both paths round to the nearest level, so they agree wherever
+        // dithering has no error to carry.
+        let level = |c: Color| {
+            Color::new(
+                c.x.round().clamp(0.0, 255.0),
+                c.y.round().clamp(0.0, 255.0),
+                c.z.round().clamp(0.0, 255.0),
+            )
+        };
+        let mut work: Vec<Color> = self.pixels.iter().map(|c| c.saturate() * 255.0).collect();
+        if !dither {
+            return work
+                .into_iter()
+                .map(|c| {
+                    let q = level(c);
+                    [q.x as u8, q.y as u8, q.z as u8]
+                })
+                .collect();
+        }
+
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut out = Vec::with_capacity(work.len());
+        for y in 0..h {
+            for x in 0..w {
+                let old = work[y * w + x];
+                let q = level(old);
+                out.push([q.x as u8, q.y as u8, q.z as u8]);
+
+                let err = old - q;
+                if x + 1 < w {
+                    work[y * w + x + 1] += err * (7.0 / 16.0);
+                }
+                if y + 1 < h {
+                    if x > 0 {
+                        work[(y + 1) * w + x - 1] += err * (3.0 / 16.0);
+                    }
+                    work[(y + 1) * w + x] += err * (5.0 / 16.0);
+                    if x + 1 < w {
+                        work[(y + 1) * w + x + 1] += err * (1.0 / 16.0);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Multiplies every pixel by the row-major color matrix `m`.
+    fn transform_colors(&self, m: &[[f64; 3]; 3]) -> Framebuffer {
+        let row = |r: [f64; 3], c: Color| r[0] * c.x + r[1] * c.y + r[2] * c.z;
+        Framebuffer {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .pixels
+                .iter()
+                .map(|&c| Color::new(row(m[0], c), row(m[1], c), row(m[2], c)))
+                .collect(),
+        }
+    }
+
     /// Export the framebuffer as a PPM (Portable Pixmap) image file.
     /// PPM P6 binary format: RGB triplets, one byte per channel, no compression.
     /// This produces a lossless image that can be viewed with most image
-    /// viewers or converted to PNG/JPEG with ImageMagick.
-    pub fn write_ppm(&self, path: &str) -> io::Result<()> {
+    /// viewers or converted to PNG/JPEG with ImageMagick. `dither` enables
+    /// error-diffusion quantization (see `quantize`).
+    pub fn write_ppm(&self, path: &str, dither: bool) -> io::Result<()> {
         let mut file = io::BufWriter::new(std::fs::File::create(path)?);
         write!(file, "P6\n{} {}\n255\n", self.width, self.height)?;
-        for pixel in &self.pixels {
-            let c = pixel.saturate();
-            let r = (c.x * 255.999) as u8;
-            let g = (c.y * 255.999) as u8;
-            let b = (c.z * 255.999) as u8;
-            file.write_all(&[r, g, b])?;
+        for rgb in self.quantize(dither) {
+            file.write_all(&rgb)?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Export as an ASCII PPM (P3) with one `r g b` pixel per line, row by
+    /// row. Several times larger than P6, but readable and diffable, which
+    /// suits tiny golden images kept in version control.
+    pub fn write_ppm_ascii(&self, path: &str, dither: bool) -> io::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        write!(file, "P3\n{} {}\n255\n", self.width, self.height)?;
+        for [r, g, b] in self.quantize(dither) {
+            writeln!(file, "{r} {g} {b}")?;
         }
         file.flush()?;
         Ok(())
     }
 }
 
+/// Linear mid-gray: the fixed point of the contrast curve and the target
+/// of auto-exposure.
+const MID_GRAY: f64 = 0.18;
+
+/// Simple look grading on a tone-mapped color. Contrast is a power curve in
+/// log space pivoting on mid-gray, so 18% gray and black stay put:
+///   c' = 0.18 · (c / 0.18)^contrast
+/// Saturation lerps each channel away from (or toward) the luminance.
+fn grade(c: Color, contrast: f64, saturation: f64) -> Color {
+    if contrast == 1.0 && saturation == 1.0 {
+        return c;
+    }
+    let curve = |v: f64| MID_GRAY * (v.max(0.0) / MID_GRAY).powf(contrast);
+    let c = Color::new(curve(c.x), curve(c.y), curve(c.z));
+    let lum = c.luminance();
+    let gray = Color::new(lum, lum, lum);
+    let graded = gray + (c - gray) * saturation;
+    Color::new(graded.x.max(0.0), graded.y.max(0.0), graded.z.max(0.0))
+}
+
+// ─── Accumulation Files ─────────────────────────────────────────────────────
+
+/// Magic header identifying a photon-cli partial accumulation file.
+const ACCUMULATION_MAGIC: &[u8; 8] = b"PHOTON1\n";
+
+impl Framebuffer {
+    /// Writes a linear HDR mean image as a `.photon` partial accumulation
+    /// file for distributed rendering. Layout (little-endian):
+    ///
+    /// ```text
+    ///   "PHOTON1\n"  width: u32  height: u32  samples: u32
+    ///   width × height × [r, g, b]: f64   — radiance *sums*, not means
+    /// ```
+    ///
+    /// Storing sums together with the sample count lets partials rendered
+    /// with different sample budgets be merged by plain addition.
+    pub fn write_accumulation(&self, path: &str, samples: u32) -> io::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        file.write_all(ACCUMULATION_MAGIC)?;
+        for v in [self.width, self.height, samples] {
+            file.write_all(&v.to_le_bytes())?;
+        }
+        let n = samples as f64;
+        for p in &self.pixels {
+            for c in [p.x, p.y, p.z] {
+                file.write_all(&(c * n).to_le_bytes())?;
+            }
+        }
+        file.flush()
+    }
+
+    /// Reads a `.photon` partial, returning the radiance sums and the number
+    /// of samples they were accumulated from.
+    pub fn read_accumulation(path: &str) -> io::Result<(Framebuffer, u32)> {
+        let bytes = std::fs::read(path)?;
+        let invalid =
+            |msg: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{path}: {msg}"));
+        if bytes.len() < 20 || &bytes[..8] != ACCUMULATION_MAGIC {
+            return Err(invalid("not a photon accumulation file"));
+        }
+        let word = |i: usize| u32::from_le_bytes(bytes[8 + 4 * i..12 + 4 * i].try_into().unwrap());
+        let (width, height, samples) = (word(0), word(1), word(2));
+        let count = width as usize * height as usize;
+        let body = &bytes[20..];
+        if body.len() != count * 3 * 8 {
+            return Err(invalid("truncated pixel data"));
+        }
+        let channel = |i: usize| f64::from_le_bytes(body[8 * i..8 * i + 8].try_into().unwrap());
+        let pixels = (0..count)
+            .map(|i| Color::new(channel(3 * i), channel(3 * i + 1), channel(3 * i + 2)))
+            .collect();
+        Ok((
+            Framebuffer {
+                width,
+                height,
+                pixels,
+            },
+            samples,
+        ))
+    }
+
+    /// Sums a set of partial accumulation files and divides by their total
+    /// sample count, yielding the same mean a single render with all samples
+    /// would have produced. Returns the merged image and that sample count.
+    pub fn merge_accumulations(paths: &[String]) -> io::Result<(Framebuffer, u32)> {
+        let mut merged: Option<Framebuffer> = None;
+        let mut total_samples = 0u32;
+        for path in paths {
+            let (partial, samples) = Framebuffer::read_accumulation(path)?;
+            total_samples = total_samples.checked_add(samples).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{path}: total sample count overflows"),
+                )
+            })?;
+            match merged.as_mut() {
+                None => merged = Some(partial),
+                Some(acc) => {
+                    if (acc.width, acc.height) != (partial.width, partial.height) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "{path}: size {}×{} does not match {}×{}",
+                                partial.width, partial.height, acc.width, acc.height
+                            ),
+                        ));
+                    }
+                    for (a, p) in acc.pixels.iter_mut().zip(&partial.pixels) {
+                        *a += *p;
+                    }
+                }
+            }
+        }
+        let mut merged = merged
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no files to merge"))?;
+        if total_samples == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "partials contain no samples",
+            ));
+        }
+        for p in &mut merged.pixels {
+            *p /= total_samples as f64;
+        }
+        Ok((merged, total_samples))
+    }
+}
+
 // ─── Render Statistics ──────────────────────────────────────────────────────
 
 /// Aggregate statistics collected during the rendering pass for diagnostic output.
@@ -174,6 +892,37 @@ impl RenderStats {
         );
         eprintln!("  {fill}");
     }
+
+    /// Aggregates repeated renders of the same scene into min/median/mean
+    /// time and throughput, which are far less noisy than a single run.
+    pub fn print_benchmark(runs: &[RenderStats]) {
+        if runs.is_empty() {
+            return;
+        }
+        let mut times: Vec<f64> = runs.iter().map(|r| r.elapsed_secs).collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let n = times.len();
+        let median = if n % 2 == 1 {
+            times[n / 2]
+        } else {
+            0.5 * (times[n / 2 - 1] + times[n / 2])
+        };
+        let mean = times.iter().sum::<f64>() / n as f64;
+        let rays = runs[0].total_rays as f64 / 1e6;
+
+        eprintln!("  Runs:     {n}");
+        eprintln!(
+            "  Time:     min {:.3}s  median {median:.3}s  mean {mean:.3}s",
+            times[0]
+        );
+        eprintln!(
+            "  Speed:    max {:.2}  median {:.2}  mean {:.2} Mrays/s",
+            rays / times[0],
+            rays / median,
+            rays / mean
+        );
+        eprintln!("  {}", "━".repeat(30));
+    }
 }
 
 // ─── Progress Reporter ──────────────────────────────────────────────────────
@@ -185,6 +934,10 @@ struct ProgressBar {
     done: u32,
     last_pct: u32,
     start: std::time::Instant,
+    /// Highest allowed rate in units per second, when throttled.
+    max_rate: Option<f64>,
+    /// Draw nothing; only count progress for the throttle.
+    hidden: bool,
 }
 
 impl ProgressBar {
@@ -194,16 +947,41 @@ impl ProgressBar {
             done: 0,
             last_pct: 0,
             start: std::time::Instant::now(),
+            max_rate: None,
+            hidden: false,
         }
     }
 
-    fn tick(&mut self) {
-        self.done += 1;
-        let pct = self.done * 100 / self.total;
-        if pct != self.last_pct {
+    /// Keeps the bar off stderr, e.g. while a live preview owns the terminal.
+    fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Bases the ETA on at most `rate` units per second, so a throttled
+    /// render does not promise the speed of its first unthrottled burst.
+    fn with_max_rate(mut self, rate: Option<f64>) -> Self {
+        self.max_rate = rate;
+        self
+    }
+
+    /// Time by which `done` units are due at the throttled rate, if any.
+    fn due(&self) -> Option<std::time::Instant> {
+        let rate = self.max_rate?;
+        Some(self.start + std::time::Duration::from_secs_f64(self.done as f64 / rate))
+    }
+
+    fn tick(&mut self, count: u32) {
+        self.done += count;
+        // An empty job is complete from the start.
+        let pct = (self.done * 100).checked_div(self.total).unwrap_or(100);
+        if pct != self.last_pct && !self.hidden {
             let elapsed = self.start.elapsed().as_secs_f64();
-            let rate = self.done as f64 / elapsed;
-            let remaining = (self.total - self.done) as f64 / rate;
+            let mut rate = self.done as f64 / elapsed;
+            if let Some(max_rate) = self.max_rate {
+                rate = rate.min(max_rate);
+            }
+            let remaining = self.total.saturating_sub(self.done) as f64 / rate;
             let bar_width = 24;
             let filled = (pct as usize * bar_width) / 100;
             let empty = bar_width - filled;
@@ -214,6 +992,9 @@ impl ProgressBar {
     }
 
     fn finish(&self) {
+        if self.hidden {
+            return;
+        }
         let elapsed = self.start.elapsed().as_secs_f64();
         let bar = "█".repeat(24);
         eprintln!("\r  Rendering: │{bar}│ 100%  {:.2}s       ", elapsed);
@@ -224,14 +1005,78 @@ impl ProgressBar {
 
 /// Monte Carlo path tracing integrator solving the rendering equation:
 ///   L_o(p, ω_o) = L_e(p, ω_o) + ∫_Ω f_r(p, ω_i, ω_o) · L_i(p, ω_i) · |cos θ_i| dω_i
-/// via importance-sampling the BRDF at each bounce.
+/// via importance-sampling the BRDF at each bounce. Diffuse bounces also aim
+/// at the emitters in `lights`, combined with BRDF sampling by one-sample MIS.
 pub struct PathTracer<'a> {
     pub scene: &'a dyn Hittable,
     pub config: &'a RenderConfig,
     pub camera: &'a Camera,
     pub sky: SkyModel,
+    /// Sun lamp in front of the sky; it should also be in `lights`.
+    pub sun: Option<SkySun>,
+    pub lights: &'a [LightHandle],
+    /// Baked irradiance that ends diffuse paths after the first bounce,
+    /// from [`PathTracer::bake_irradiance`].
+    pub irradiance: Option<&'a IrradianceCache>,
 }
 
+/// Receives each progressive pass's report line and running mean.
+pub type PassPreview<'a> = dyn FnMut(&str, &Framebuffer) + 'a;
+
+/// Fraction of diffuse bounces sampled toward the light list when the scene
+/// has lights; the rest are shared between BRDF sampling and path guiding.
+const LIGHT_MIX: f64 = 0.5;
+
+/// Odd 64-bit constant (2^64 / φ) that spreads pixel indices across the
+/// seed space before they are hashed with a pass seed.
+const PIXEL_SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Seed for one pixel's sample sequence: the pixel index and pass seed run
+/// through the SplitMix64 finalizer, so neighbouring pixels get unrelated
+/// streams and low-spp noise stays free of diagonal or row structure.
+fn pixel_seed(seed: u64, x: u32, y: u32) -> u64 {
+    let index = (u64::from(y) << 32) | u64::from(x);
+    let mut z = seed ^ index.wrapping_add(1).wrapping_mul(PIXEL_SEED_MIX);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Film coordinates of a position within a `w`×`h` frame, in pixels from
+/// its bottom-left corner. Positions are frame-relative, so a letterbox
+/// offset never enters, and a one-pixel-wide or -tall frame maps to 0
+/// instead of dividing by zero.
+pub fn film_uv(x: f64, y: f64, w: u32, h: u32) -> (f64, f64) {
+    (x / f64::from((w - 1).max(1)), y / f64::from((h - 1).max(1)))
+}
+
+/// Center of the `index`-th `n`-pixel block along an axis of `extent`
+/// pixels, where a preview render traces its one pixel. The last block may
+/// be cut short.
+fn block_center(index: u32, n: u32, extent: u32) -> u32 {
+    let start = index * n;
+    start + n.min(extent - start) / 2
+}
+
+/// Separates a pixel's sampler-shift stream from its sample stream, which
+/// share the pixel seed in a render's first pass.
+const SHIFT_SEED_SALT: u64 = 0xD1B5_4A32_D192_ED03;
+
+/// Cache records placed along the longer side of the frame by
+/// `--sh-cache`; the grid of camera rays is this many pixels apart.
+const SH_CACHE_GRID: u32 = 32;
+/// Path-traced radiance samples projected onto SH per cache record.
+const SH_RECORD_SAMPLES: u32 = 256;
+
+/// Half-width of the in-focus band for `--focus-preview`, as a fraction of
+/// the focus distance.
+const FOCUS_BAND: f64 = 0.03;
+/// Radiance that in-focus surfaces are blended toward in the preview.
+const FOCUS_TINT: Color = Color::new(0.1, 1.0, 0.1);
+
+/// The matte gray substituted for every non-emissive material in clay mode.
+const CLAY: Lambertian = Lambertian::new(Color::new(0.5, 0.5, 0.5));
+
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
 /// Environment lighting model for rays that escape the scene geometry.
@@ -239,6 +1084,7 @@ pub enum SkyModel {
     Gradient { horizon: Color, zenith: Color },
     Solid(Color),
     Black,
+    Physical(PhysicalSky),
 }
 
 impl SkyModel {
@@ -251,72 +1097,649 @@ impl SkyModel {
             }
             SkyModel::Solid(color) => *color,
             SkyModel::Black => Color::zero(),
+            SkyModel::Physical(sky) => sky.sample(ray.direction.normalized()),
+        }
+    }
+}
+
+/// Converts Preetham luminance (kcd/m²) into the renderer's radiance units,
+/// putting a clear midday zenith near the brightness of the gradient sky.
+const SKY_LUMINANCE_SCALE: f64 = 0.1;
+/// Angular radius of the sun disk. About twice the real sun, so the disk
+/// still resolves at terminal resolutions.
+const SUN_ANGULAR_RADIUS: f64 = 0.5 * std::f64::consts::PI / 180.0;
+/// Luminance of the sun disk in radiance units.
+const SUN_LUMINANCE: f64 = 20.0;
+
+/// Analytic daylight sky after Preetham, Shirley & Smits (1999): the Perez
+/// distribution of luminance and CIE chromaticity, fitted over turbidity
+/// (haze) and sun position, plus a bright sun disk. Directions below the
+/// horizon see the horizon color.
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicalSky {
+    sun: Vec3,
+    /// Perez coefficients A–E for Y, x and y.
+    perez: [[f64; 5]; 3],
+    /// Zenith Y, x, y divided by the Perez function at the zenith, so a
+    /// direction's value is just `perez(θ, γ) * zenith[i]`.
+    zenith: [f64; 3],
+    sun_color: Color,
+    /// Whether escaping rays see the sun disk.
+    sun_disk: bool,
+}
+
+impl PhysicalSky {
+    /// `sun` points toward the sun and must lie above the horizon;
+    /// `turbidity` ranges from about 2 (clear) to 10 (hazy).
+    pub fn new(sun: Vec3, turbidity: f64) -> Self {
+        let sun = sun.normalized();
+        let t = turbidity;
+        let theta_s = sun.y.clamp(0.0, 1.0).acos();
+        let (t2, th2, th3) = (t * t, theta_s * theta_s, theta_s * theta_s * theta_s);
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (std::f64::consts::PI - 2.0 * theta_s);
+        let zenith_y = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let zenith_x = t2 * (0.00166 * th3 - 0.00375 * th2 + 0.00209 * theta_s)
+            + t * (-0.02903 * th3 + 0.06377 * th2 - 0.03202 * theta_s + 0.00394)
+            + (0.11693 * th3 - 0.21196 * th2 + 0.06052 * theta_s + 0.25886);
+        let zenith_yc = t2 * (0.00275 * th3 - 0.00610 * th2 + 0.00317 * theta_s)
+            + t * (-0.04214 * th3 + 0.08970 * th2 - 0.04153 * theta_s + 0.00516)
+            + (0.15346 * th3 - 0.26756 * th2 + 0.06670 * theta_s + 0.26688);
+
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+        let zenith_values = [zenith_y.max(0.0), zenith_x, zenith_yc];
+        let mut zenith = [0.0; 3];
+        for i in 0..3 {
+            zenith[i] = zenith_values[i] / perez_distribution(&perez[i], 1.0, theta_s.cos());
+        }
+
+        let mut sky = Self {
+            sun,
+            perez,
+            zenith,
+            sun_color: Color::zero(),
+            sun_disk: true,
+        };
+        let toward_sun = sky.sky_color(sun);
+        let luminance = toward_sun.luminance();
+        if luminance > 0.0 {
+            sky.sun_color = toward_sun * (SUN_LUMINANCE / luminance);
+        }
+        sky
+    }
+
+    /// Color of the sun disk, for tinting a `--sky-sun` lamp to match.
+    pub fn sun_color(&self) -> Color {
+        self.sun_color
+    }
+
+    /// Drops the sun disk, for when a `--sky-sun` lamp stands in for it;
+    /// keeping both would count the sun twice.
+    pub fn without_sun_disk(mut self) -> Self {
+        self.sun_disk = false;
+        self
+    }
+
+    fn sample(&self, dir: Vec3) -> Color {
+        if self.sun_disk && dir.dot(self.sun) >= SUN_ANGULAR_RADIUS.cos() {
+            return self.sun_color;
+        }
+        self.sky_color(dir)
+    }
+
+    /// Sky radiance toward unit direction `dir`, without the sun disk.
+    fn sky_color(&self, dir: Vec3) -> Color {
+        // Keep the horizon finite: the Perez term blows up as cos θ → 0.
+        let cos_theta = dir.y.max(0.01);
+        let cos_gamma = dir.dot(self.sun).clamp(-1.0, 1.0);
+        let [lum, x, y] = [0, 1, 2]
+            .map(|i| self.zenith[i] * perez_distribution(&self.perez[i], cos_theta, cos_gamma));
+        xyy_to_rgb(x, y, lum * SKY_LUMINANCE_SCALE)
+    }
+}
+
+/// Perez sky distribution F(θ, γ) for one channel's coefficients, where θ is
+/// the view zenith angle and γ the angle between view and sun.
+fn perez_distribution(c: &[f64; 5], cos_theta: f64, cos_gamma: f64) -> f64 {
+    let gamma = cos_gamma.acos();
+    (1.0 + c[0] * (c[1] / cos_theta).exp())
+        * (1.0 + c[2] * (c[3] * gamma).exp() + c[4] * cos_gamma * cos_gamma)
+}
+
+/// CIE xyY → linear sRGB (D65), clamped to non-negative.
+fn xyy_to_rgb(x: f64, y: f64, lum: f64) -> Color {
+    if y <= 0.0 {
+        return Color::zero();
+    }
+    let cx = x / y * lum;
+    let cz = (1.0 - x - y) / y * lum;
+    Color::new(
+        (3.2406 * cx - 1.5372 * lum - 0.4986 * cz).max(0.0),
+        (-0.9689 * cx + 1.8758 * lum + 0.0415 * cz).max(0.0),
+        (0.0557 * cx - 0.2040 * lum + 1.0570 * cz).max(0.0),
+    )
+}
+
+// ─── Path Guiding ───────────────────────────────────────────────────────────
+
+/// Number of equal-area bins along cos θ (world y) in the guiding histogram.
+const GUIDE_Z_BINS: usize = 4;
+/// Number of equal-area bins along the azimuth φ.
+const GUIDE_PHI_BINS: usize = 8;
+const GUIDE_BINS: usize = GUIDE_Z_BINS * GUIDE_PHI_BINS;
+/// Samples a guide must have recorded before it is trusted for sampling.
+const GUIDE_WARMUP: u32 = 32;
+/// Maximum fraction of the bounces not spent on lights that are guided; the
+/// rest use BRDF sampling, which keeps every direction with a non-zero BRDF
+/// reachable.
+const GUIDE_MIX: f64 = 0.5;
+
+/// A lightweight path-guiding distribution: a running estimate of the mean
+/// incoming radiance per direction bin on the sphere, weighted by the BRDF
+/// lobe (i.e. the integrand the bounce estimates), learned from the
+/// samples already taken for one pixel. Bins use the Lambert cylindrical
+/// equal-area mapping (uniform in cos θ and φ), so every bin subtends
+/// 4π / GUIDE_BINS steradians and the pdf is piecewise constant.
+///
+/// Guided directions are mixed with BRDF samples and weighted by the
+/// one-sample MIS mixture pdf, so the estimate stays unbiased no matter
+/// how good or bad the learned distribution is.
+struct DirectionGuide {
+    sum: [f32; GUIDE_BINS],
+    count: [u32; GUIDE_BINS],
+    recorded: u32,
+}
+
+impl DirectionGuide {
+    fn new() -> Self {
+        Self {
+            sum: [0.0; GUIDE_BINS],
+            count: [0; GUIDE_BINS],
+            recorded: 0,
+        }
+    }
+
+    fn bin(direction: Vec3) -> usize {
+        let d = direction.normalized();
+        let z = ((d.y + 1.0) * 0.5 * GUIDE_Z_BINS as f64) as usize;
+        let phi = (d.z.atan2(d.x) + std::f64::consts::PI) / std::f64::consts::TAU;
+        let p = (phi * GUIDE_PHI_BINS as f64) as usize;
+        z.min(GUIDE_Z_BINS - 1) * GUIDE_PHI_BINS + p.min(GUIDE_PHI_BINS - 1)
+    }
+
+    /// Sampling weight of each bin: its mean radiance plus a small floor so
+    /// directions that have not paid off yet are still explored.
+    fn weights(&self) -> ([f64; GUIDE_BINS], f64) {
+        let mut w = [0.0; GUIDE_BINS];
+        let mut total = 0.0;
+        for (i, wi) in w.iter_mut().enumerate() {
+            *wi = self.sum[i] as f64 / self.count[i].max(1) as f64;
+            total += *wi;
+        }
+        let floor = (total / GUIDE_BINS as f64).max(1e-6) * 0.05;
+        for wi in &mut w {
+            *wi += floor;
+        }
+        (w, total + floor * GUIDE_BINS as f64)
+    }
+
+    fn ready(&self) -> bool {
+        self.recorded >= GUIDE_WARMUP
+    }
+
+    fn record(&mut self, direction: Vec3, contribution: Color) {
+        let i = Self::bin(direction);
+        self.sum[i] += contribution.luminance().max(0.0) as f32;
+        self.count[i] += 1;
+        self.recorded += 1;
+    }
+}
+
+impl Pdf for DirectionGuide {
+    fn generate(&self, rng: &mut dyn rand::RngCore) -> Vec3 {
+        let (w, total) = self.weights();
+        let mut target = rng.gen::<f64>() * total;
+        let mut bin = GUIDE_BINS - 1;
+        for (i, wi) in w.iter().enumerate() {
+            if target < *wi {
+                bin = i;
+                break;
+            }
+            target -= wi;
         }
+        let (iz, ip) = (bin / GUIDE_PHI_BINS, bin % GUIDE_PHI_BINS);
+        let z = -1.0 + 2.0 * (iz as f64 + rng.gen::<f64>()) / GUIDE_Z_BINS as f64;
+        let phi = std::f64::consts::TAU * (ip as f64 + rng.gen::<f64>()) / GUIDE_PHI_BINS as f64
+            - std::f64::consts::PI;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        Vec3::new(r * phi.cos(), z, r * phi.sin())
+    }
+
+    fn value(&self, direction: Vec3) -> f64 {
+        let (w, total) = self.weights();
+        let bin_solid_angle = 4.0 * std::f64::consts::PI / GUIDE_BINS as f64;
+        w[Self::bin(direction)] / total / bin_solid_angle
     }
 }
 
 impl<'a> PathTracer<'a> {
+    /// Blends a camera sample toward green when its first hit lies within
+    /// `FOCUS_BAND` of the focus plane.
+    fn focus_tint(&self, ray: &Ray, radiance: Color) -> Color {
+        match self.scene.hit(ray, self.config.ray_epsilon, f64::INFINITY) {
+            Some(hit) if self.camera.focus_offset(hit.point).abs() < FOCUS_BAND => {
+                radiance.lerp(FOCUS_TINT, 0.6)
+            }
+            _ => radiance,
+        }
+    }
+
     /// Traces a single ray recursively through the scene, accumulating
-    /// radiance from emissive surfaces and scattered light.
-    fn trace_ray(&self, ray: &Ray, depth: u32, rng: &mut SmallRng) -> Color {
+    /// radiance from emissive surfaces and scattered light. `guide` is only
+    /// passed for primary rays; it both steers and learns from the first
+    /// bounce.
+    fn trace_ray(
+        &self,
+        ray: &Ray,
+        depth: u32,
+        rng: &mut Sampler,
+        guide: Option<&mut DirectionGuide>,
+    ) -> Color {
         if depth >= self.config.max_bounces {
             return Color::zero();
         }
+        rng.start_bounce(depth);
 
-        // t_min = 0.001 prevents shadow acne caused by floating-point self-intersection
-        if let Some(hit) = self.scene.hit(ray, 0.001, f64::INFINITY) {
+        // t_min = ray_epsilon prevents shadow acne caused by floating-point self-intersection
+        if let Some(mut hit) = self.scene.hit(ray, self.config.ray_epsilon, f64::INFINITY) {
+            if self.config.clay && !hit.material.is_emissive() {
+                hit.material = &CLAY;
+            }
             let emitted = hit.material.emitted();
 
-            if let Some((scattered, attenuation)) = hit.material.scatter(ray, &hit, rng) {
-                let incoming = self.trace_ray(&scattered, depth + 1, rng);
-                emitted + attenuation.hadamard(incoming)
+            let Some((scattered, attenuation)) = hit.material.scatter(ray, &hit, rng) else {
+                return emitted;
+            };
+
+            let brdf_sampled = !hit.material.is_specular()
+                && hit.material.scattering_pdf(ray, &hit, &scattered) > 0.0;
+            // Past the first bounce, a diffuse surface reflects the cached
+            // irradiance: albedo · E / π for the cosine-weighted BRDFs. The
+            // cache only holds light arriving on the normal's side, so
+            // diffuse transmission keeps tracing.
+            let reflected = scattered.direction.dot(hit.normal) > 0.0;
+            if let Some(cache) = self
+                .irradiance
+                .filter(|_| brdf_sampled && reflected && depth > 0)
+            {
+                if let Some(irradiance) = cache.lookup(hit.point, hit.normal) {
+                    return emitted + attenuation.hadamard(irradiance) / std::f64::consts::PI;
+                }
+            }
+            if !brdf_sampled || (guide.is_none() && self.lights.is_empty()) {
+                let incoming = self.trace_ray(&scattered, depth + 1, rng, None);
+                return emitted + attenuation.hadamard(incoming);
+            }
+
+            let light_mix = if self.lights.is_empty() {
+                0.0
+            } else {
+                LIGHT_MIX
+            };
+            let guide_mix = match guide.as_deref() {
+                Some(guide) if guide.ready() => {
+                    GUIDE_MIX * hit.material.guiding_importance().clamp(0.0, 1.0)
+                }
+                _ => 0.0,
+            };
+
+            // One-sample MIS: draw from the mixture of lights, guide, and
+            // BRDF, then weight by the BRDF pdf over the mixture pdf.
+            let brdf = ScatterPdf {
+                ray,
+                hit: &hit,
+                sampled: scattered.direction,
+            };
+            let lights = LightPdf {
+                lights: self.lights,
+                origin: hit.point,
+                t_min: LIGHT_QUERY_T_MIN * self.config.scene_scale(),
+            };
+            let (direction, pdf) = {
+                let guided: &dyn Pdf = match guide.as_deref() {
+                    Some(guide) => guide,
+                    None => &brdf,
+                };
+                let bounce = MixturePdf::new(guided, &brdf, guide_mix);
+                let mixture = MixturePdf::new(&lights, &bounce, light_mix);
+                let direction = mixture.generate(rng);
+                (direction, mixture.value(direction))
+            };
+            let scattered = Ray::with_time(hit.point, direction, ray.time);
+            let brdf_pdf = brdf.value(direction);
+            let incoming = if brdf_pdf > 0.0 {
+                self.trace_ray(&scattered, depth + 1, rng, None)
             } else {
-                emitted
+                Color::zero()
+            };
+            if let Some(guide) = guide {
+                guide.record(scattered.direction, incoming * brdf_pdf);
             }
+            emitted + attenuation.hadamard(incoming) * (brdf_pdf / pdf)
         } else {
-            self.sky.sample(ray)
+            match self.config.background {
+                Some(background) if depth == 0 => background,
+                _ => self
+                    .sun
+                    .and_then(|sun| sun.radiance_toward(ray.direction.normalized()))
+                    .unwrap_or_else(|| self.sky.sample(ray)),
+            }
         }
     }
 
-    /// Renders the full image into a framebuffer with stratified pixel sampling.
+    /// Bakes the `--sh-cache` irradiance cache. A coarse grid of camera rays
+    /// places records at its first two diffuse hits, and each record
+    /// projects `SH_RECORD_SAMPLES` cosine-weighted path-traced radiance
+    /// samples onto spherical harmonics.
+    pub fn bake_irradiance(&self) -> IrradianceCache {
+        let (_, _, w, h) = self.config.frame_rect();
+        let seed = self
+            .config
+            .seed
+            .unwrap_or_else(|| SmallRng::from_entropy().gen());
+        // The grid runs over the pixels the render traces: every `step`-th
+        // downscale block, at the block's center.
+        let n = self.config.downscale.max(1);
+        let step = (w.max(h) / SH_CACHE_GRID / n).max(1);
+
+        let mut points = Vec::new();
+        let mut rng = SmallRng::seed_from_u64(pixel_seed(seed, u32::MAX, u32::MAX));
+        for by in (step / 2..h.div_ceil(n)).step_by(step as usize) {
+            for bx in (step / 2..w.div_ceil(n)).step_by(step as usize) {
+                let x = block_center(bx, n, w);
+                let y = h - 1 - block_center(by, n, h);
+                let (u, v) = film_uv(f64::from(x), f64::from(y), w, h);
+                let mut ray = self.camera.get_ray(u, v, &mut rng);
+                for _ in 0..2 {
+                    let Some(hit) = self.scene.hit(&ray, self.config.ray_epsilon, f64::INFINITY)
+                    else {
+                        break;
+                    };
+                    let Some((scattered, _)) = hit.material.scatter(&ray, &hit, &mut rng) else {
+                        break;
+                    };
+                    if hit.material.scattering_pdf(&ray, &hit, &scattered) > 0.0 {
+                        points.push((hit.point, hit.normal.normalized()));
+                    }
+                    ray = scattered;
+                }
+            }
+        }
+
+        let threads = self.config.threads.clamp(1, points.len().max(1));
+        let chunk = points.len().div_ceil(threads).max(1);
+        let records: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = points
+                .chunks(chunk)
+                .enumerate()
+                .map(|(c, points)| {
+                    scope.spawn(move || {
+                        points
+                            .iter()
+                            .enumerate()
+                            .map(|(i, &(point, normal))| {
+                                let index = (c * chunk + i) as u32;
+                                let mut rng = Sampler::new(
+                                    SmallRng::seed_from_u64(pixel_seed(seed, index, u32::MAX - 1)),
+                                    SamplerKind::Random,
+                                    0,
+                                );
+                                (point, normal, self.sample_radiance(point, normal, &mut rng))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+        IrradianceCache::new(records)
+    }
+
+    /// Projects path-traced radiance arriving over the hemisphere of
+    /// `normal` at `point` onto spherical harmonics.
+    fn sample_radiance(&self, point: Point3, normal: Vec3, rng: &mut Sampler) -> ShRadiance {
+        let mut sh = ShRadiance::zero();
+        for _ in 0..SH_RECORD_SAMPLES {
+            let direction = (normal + Vec3::random_unit_vector(rng)).normalized();
+            let cosine = direction.dot(normal);
+            if cosine <= 1e-6 {
+                continue;
+            }
+            let radiance = self.trace_ray(&Ray::new(point, direction), 1, rng, None);
+            let pdf = cosine / std::f64::consts::PI;
+            sh.add_sample(direction, radiance, 1.0 / (pdf * SH_RECORD_SAMPLES as f64));
+        }
+        sh
+    }
+
+    /// Renders the full image into a linear HDR framebuffer with stratified
+    /// pixel sampling. Tone mapping and gamma are left to `post_process`, so
+    /// the radiance estimate stays available to the display encoders.
     /// Returns both the framebuffer and render statistics.
+    ///
+    /// Only the `frame_rect` is traced; pixels outside it stay black.
     pub fn render(&self) -> (Framebuffer, RenderStats) {
-        let w = self.config.width;
-        let h = self.config.height;
+        let seed = self
+            .config
+            .seed
+            .unwrap_or_else(|| SmallRng::from_entropy().gen());
+        self.render_pass(seed, self.config.first_sample, true)
+    }
+
+    /// Progressive refinement: renders passes of `samples_per_pixel` each
+    /// into a running mean and, after every pass, reports to stderr the mean
+    /// absolute change of the clamped image inside the frame since the
+    /// previous pass. Stops after `max_passes`, or as soon as the change
+    /// drops below `converge`. Each pass is seeded from its first sample
+    /// index, like a `--sample-range` partial, and continues the sampler's
+    /// sequence where the previous pass stopped.
+    ///
+    /// With `live`, the progress bar is hidden and each pass hands its
+    /// report line and the running mean to the callback instead of stderr.
+    pub fn render_progressive(
+        &self,
+        max_passes: u32,
+        converge: Option<f64>,
+        mut live: Option<&mut PassPreview>,
+    ) -> (Framebuffer, RenderStats) {
         let spp = self.config.samples_per_pixel;
-        let mut fb = Framebuffer::new(w, h);
-        let mut rng = SmallRng::from_entropy();
+        let (x0, y0, w, h) = self.config.frame_rect();
+        let mut mean = Framebuffer::new(self.config.width, self.config.height);
+        let mut stats = RenderStats {
+            total_rays: 0,
+            elapsed_secs: 0.0,
+            width: w,
+            height: h,
+            spp: 0,
+        };
 
-        let total = w * h;
-        let mut progress = ProgressBar::new(total);
-        let t0 = std::time::Instant::now();
+        // Unseeded renders draw one base seed for all passes, so the passes
+        // share one low-discrepancy sequence.
+        let seed = self
+            .config
+            .seed
+            .unwrap_or_else(|| SmallRng::from_entropy().gen());
+        for pass in 1..=max_passes {
+            let first_sample = self.config.first_sample + (pass - 1) * spp;
+            let (fb, pass_stats) = self.render_pass(seed, first_sample, live.is_none());
+            stats.total_rays += pass_stats.total_rays;
+            stats.elapsed_secs += pass_stats.elapsed_secs;
+            stats.spp += spp;
 
-        for y in (0..h).rev() {
-            for x in 0..w {
-                let mut pixel_color = Color::zero();
-                for _ in 0..spp {
-                    let u = (x as f64 + rng.gen::<f64>()) / (w - 1) as f64;
-                    let v = (y as f64 + rng.gen::<f64>()) / (h - 1) as f64;
-                    let ray = self.camera.get_ray(u, v, &mut rng);
-                    pixel_color += self.trace_ray(&ray, 0, &mut rng);
+            let mut change = 0.0;
+            for y in y0..y0 + h {
+                for x in x0..x0 + w {
+                    let old = mean.get(x, y);
+                    let new = old + (fb.get(x, y) - old) / pass as f64;
+                    let d = new.saturate() - old.saturate();
+                    change += d.x.abs() + d.y.abs() + d.z.abs();
+                    mean.set(x, y, new);
                 }
-                pixel_color /= spp as f64;
-
-                // Apply tone mapping in linear space before gamma correction
-                pixel_color = self.config.tone_map.apply(pixel_color);
+            }
+            change /= 3.0 * (w * h) as f64;
 
-                if self.config.gamma {
-                    pixel_color = pixel_color.gamma_correct();
+            let mut report = format!("  Pass {pass}: {} spp", stats.spp);
+            let converged = pass > 1 && converge.is_some_and(|threshold| change < threshold);
+            if pass > 1 {
+                report += &format!(", mean change {change:.5}");
+            }
+            match live.as_mut() {
+                Some(live) => {
+                    if converged {
+                        report += &format!(", converged below {:.5}", converge.unwrap_or_default());
+                    }
+                    live(&report, &mean);
+                }
+                None => {
+                    eprintln!("{report}");
+                    if converged {
+                        eprintln!("  Converged below {:.5}", converge.unwrap_or_default());
+                    }
                 }
-
-                fb.set(x, h - 1 - y, pixel_color);
-                progress.tick();
+            }
+            if converged {
+                break;
             }
         }
-        progress.finish();
+
+        (mean, stats)
+    }
+
+    /// One full-frame render of `samples_per_pixel` samples, numbered from
+    /// `first_sample` within the render that `seed` identifies, with a
+    /// progress bar on stderr when `show_progress` is set.
+    fn render_pass(
+        &self,
+        seed: u64,
+        first_sample: u32,
+        show_progress: bool,
+    ) -> (Framebuffer, RenderStats) {
+        let spp = self.config.samples_per_pixel;
+        let mut fb = Framebuffer::new(self.config.width, self.config.height);
+        let (x0, y0, w, h) = self.config.frame_rect();
+        // Preview downscaling traces one pixel per n×n block and repeats it
+        // over the block (nearest-neighbor upscaling).
+        let n = self.config.downscale.max(1);
+        let (blocks_x, blocks_y) = (w.div_ceil(n), h.div_ceil(n));
+        let threads = self.config.threads.clamp(1, blocks_y as usize);
+
+        // The throttle is counted in pixels per second, the unit of progress.
+        let max_rate = self
+            .config
+            .throttle
+            .map(|cap| cap * 1e6 * f64::from(n * n) / spp as f64);
+        let progress = Mutex::new(
+            ProgressBar::new(w * h)
+                .with_max_rate(max_rate)
+                .hidden(!show_progress),
+        );
+        let t0 = std::time::Instant::now();
+
+        // Workers pull whole framebuffer rows (blocks of rows when
+        // downscaled) from a shared queue. Each pixel seeds its own RNG from
+        // the pass seed, so a seeded render is identical whatever the thread
+        // count. The sampler's per-pixel shift comes from the render seed
+        // alone, so every pass and partial draws from the same sequence.
+        let pass_seed = seed.wrapping_add(u64::from(first_sample));
+        let stride = self.config.width as usize;
+        let start = y0 as usize * stride;
+        let rows = Mutex::new(
+            fb.pixels[start..start + h as usize * stride]
+                .chunks_mut(stride * n as usize)
+                .enumerate(),
+        );
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let Some((b, block_rows)) = rows.lock().unwrap().next() else {
+                        break;
+                    };
+                    let row_count = (block_rows.len() / stride) as u32;
+                    let y = h - 1 - block_center(b as u32, n, h);
+                    for bx in 0..blocks_x {
+                        let block_w = n.min(w - bx * n);
+                        let x = block_center(bx, n, w);
+                        let mut rng = Sampler::new(
+                            SmallRng::seed_from_u64(pixel_seed(pass_seed, x, y)),
+                            self.config.sampler,
+                            pixel_seed(seed, x, y) ^ SHIFT_SEED_SALT,
+                        );
+                        let mut pixel_color = Color::zero();
+                        let mut guide = self.config.guiding.then(DirectionGuide::new);
+                        for s in 0..spp {
+                            rng.start_sample(first_sample + s);
+                            let (u, v) = film_uv(
+                                x as f64 + rng.gen::<f64>(),
+                                y as f64 + rng.gen::<f64>(),
+                                w,
+                                h,
+                            );
+                            let ray = self.camera.get_ray(u, v, &mut rng);
+                            let radiance = self.trace_ray(&ray, 0, &mut rng, guide.as_mut());
+                            pixel_color += if self.config.focus_preview {
+                                self.focus_tint(&ray, radiance)
+                            } else {
+                                radiance
+                            };
+                        }
+                        let color = pixel_color / spp as f64;
+                        for row in block_rows.chunks_mut(stride) {
+                            let from = (x0 + bx * n) as usize;
+                            row[from..from + block_w as usize].fill(color);
+                        }
+                    }
+                    let due = {
+                        let mut progress = progress.lock().unwrap();
+                        progress.tick(w * row_count);
+                        progress.due()
+                    };
+                    if let Some(due) = due {
+                        std::thread::sleep(
+                            due.saturating_duration_since(std::time::Instant::now()),
+                        );
+                    }
+                });
+            }
+        });
+        progress.into_inner().unwrap().finish();
 
         let elapsed = t0.elapsed();
-        let total_rays = w as u64 * h as u64 * spp as u64;
+        let total_rays = blocks_x as u64 * blocks_y as u64 * spp as u64;
 
         let stats = RenderStats {
             total_rays,
@@ -332,66 +1755,274 @@ impl<'a> PathTracer<'a> {
 
 // ─── Terminal Display Engine ────────────────────────────────────────────────
 
-pub fn display_framebuffer(fb: &Framebuffer, mode: OutputMode) {
+/// Writes the display-referred image `fb` to stdout. The braille and ASCII
+/// encoders additionally consult the linear `hdr` buffer when
+/// `config.log_luminance` is set.
+pub fn display_framebuffer(fb: &Framebuffer, hdr: &Framebuffer, config: &RenderConfig) {
     let stdout = io::stdout();
     let mut out = io::BufWriter::new(stdout.lock());
 
-    match mode {
-        OutputMode::TrueColor => display_truecolor(&mut out, fb),
-        OutputMode::HalfBlock => display_halfblock(&mut out, fb),
-        OutputMode::Ascii => display_ascii(&mut out, fb),
-        OutputMode::Braille => display_braille(&mut out, fb),
+    let (cols, cells) = encode_cells(fb, hdr, config);
+    for row in cells.chunks(cols) {
+        for cell in row {
+            write_cell(&mut out, cell);
+        }
+        let _ = writeln!(out);
     }
     let _ = out.flush();
 }
 
-fn display_truecolor(out: &mut impl Write, fb: &Framebuffer) {
+/// One terminal character cell: a glyph with optional 24-bit foreground and
+/// background colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    glyph: char,
+    fg: Option<(u8, u8, u8)>,
+    bg: Option<(u8, u8, u8)>,
+}
+
+impl Cell {
+    /// Whether redrawing `self` over `shown` is worth the bytes: the glyph
+    /// changed, or a color channel moved by more than `threshold`.
+    fn differs(&self, shown: &Cell, threshold: u8) -> bool {
+        fn channels(a: Option<(u8, u8, u8)>, b: Option<(u8, u8, u8)>, threshold: u8) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => {
+                    a.0.abs_diff(b.0) > threshold
+                        || a.1.abs_diff(b.1) > threshold
+                        || a.2.abs_diff(b.2) > threshold
+                }
+                (a, b) => a.is_some() != b.is_some(),
+            }
+        }
+        self.glyph != shown.glyph
+            || channels(self.fg, shown.fg, threshold)
+            || channels(self.bg, shown.bg, threshold)
+    }
+}
+
+fn write_cell(out: &mut impl Write, cell: &Cell) {
+    let rgb = |(r, g, b)| style::Color::Rgb { r, g, b };
+    let _ = match (cell.fg, cell.bg) {
+        (Some(fg), Some(bg)) => write!(out, "{}", cell.glyph.with(rgb(fg)).on(rgb(bg))),
+        (Some(fg), None) => write!(out, "{}", cell.glyph.with(rgb(fg))),
+        (None, Some(bg)) => write!(out, "{}", cell.glyph.on(rgb(bg))),
+        (None, None) => write!(out, "{}", cell.glyph),
+    };
+}
+
+/// Encodes `fb` into terminal cells for the configured output mode,
+/// returning the number of columns and the cells in row-major order.
+fn encode_cells(fb: &Framebuffer, hdr: &Framebuffer, config: &RenderConfig) -> (usize, Vec<Cell>) {
+    let corrected;
+    let fb = match config.display_matrix {
+        Some(m) => {
+            corrected = fb.transform_colors(&m);
+            &corrected
+        }
+        None => fb,
+    };
+    match config.output_mode {
+        OutputMode::TrueColor => (fb.width as usize, cells_truecolor(fb)),
+        OutputMode::HalfBlock => (fb.width as usize, cells_halfblock(fb)),
+        OutputMode::Ascii => {
+            let levels = structure_levels(fb, hdr, config.log_luminance);
+            (fb.width as usize, cells_ascii(fb, &levels))
+        }
+        OutputMode::Braille => {
+            let levels = structure_levels(fb, hdr, config.log_luminance);
+            ((fb.width / 2) as usize, cells_braille(fb, &levels))
+        }
+    }
+}
+
+// ─── Live Display ───────────────────────────────────────────────────────────
+
+/// In-place terminal preview for progressive renders. The first draw prints
+/// the whole image; later draws move the cursor back over it and rewrite
+/// only the cells that changed by more than a threshold, which keeps
+/// escape-sequence traffic small on slow links such as SSH. A status line
+/// sits below the image.
+pub struct LiveDisplay {
+    threshold: u8,
+    cols: usize,
+    /// Cells as currently shown on screen.
+    shown: Vec<Cell>,
+}
+
+impl LiveDisplay {
+    /// `threshold` is the per-channel change (0–255) a cell needs before it
+    /// is redrawn.
+    pub fn new(threshold: u8) -> Self {
+        Self {
+            threshold,
+            cols: 0,
+            shown: Vec::new(),
+        }
+    }
+
+    /// Draws a preview of `fb`, followed by `status` and the number of cells
+    /// rewritten.
+    pub fn draw(
+        &mut self,
+        fb: &Framebuffer,
+        hdr: &Framebuffer,
+        config: &RenderConfig,
+        status: &str,
+    ) {
+        self.update(fb, hdr, config, status, self.threshold);
+    }
+
+    /// Draws the final image exactly, so the screen matches a full redraw,
+    /// and leaves the cursor below the status line.
+    pub fn finish(
+        &mut self,
+        fb: &Framebuffer,
+        hdr: &Framebuffer,
+        config: &RenderConfig,
+        status: &str,
+    ) {
+        self.update(fb, hdr, config, status, 0);
+        println!();
+    }
+
+    fn update(
+        &mut self,
+        fb: &Framebuffer,
+        hdr: &Framebuffer,
+        config: &RenderConfig,
+        status: &str,
+        threshold: u8,
+    ) {
+        let stdout = io::stdout();
+        let mut out = io::BufWriter::new(stdout.lock());
+        let (cols, cells) = encode_cells(fb, hdr, config);
+        let rows = cells.len() / cols;
+
+        let mut written = 0;
+        if self.shown.len() != cells.len() || self.cols != cols {
+            for row in cells.chunks(cols) {
+                for cell in row {
+                    write_cell(&mut out, cell);
+                }
+                let _ = writeln!(out);
+            }
+            written = cells.len();
+            self.cols = cols;
+            self.shown = cells;
+        } else {
+            // The cursor sits on the status line, one below the image.
+            let _ = write!(out, "\r{}", cursor::MoveUp(rows as u16));
+            let mut row = 0;
+            for (i, cell) in cells.iter().enumerate() {
+                if !cell.differs(&self.shown[i], threshold) {
+                    continue;
+                }
+                let (r, c) = (i / cols, i % cols);
+                if r > row {
+                    let _ = write!(out, "{}", cursor::MoveDown((r - row) as u16));
+                    row = r;
+                }
+                let _ = write!(out, "{}", cursor::MoveToColumn(c as u16));
+                write_cell(&mut out, cell);
+                self.shown[i] = *cell;
+                written += 1;
+            }
+            if rows > row {
+                let _ = write!(out, "{}", cursor::MoveDown((rows - row) as u16));
+            }
+        }
+        let _ = write!(
+            out,
+            "\r{status} ({written}/{} cells redrawn){}",
+            self.shown.len(),
+            terminal::Clear(terminal::ClearType::UntilNewLine)
+        );
+        let _ = out.flush();
+    }
+}
+
+/// Per-pixel structure levels in [0, 1] used by the braille threshold and the
+/// ASCII density ramp.
+///
+/// By default this is the luminance of the display image, which ties the
+/// encoders to whatever the tone mapper did. With `log_luminance` the levels
+/// come from the log of the linear HDR luminance, normalized between the 1st
+/// and 99th percentiles — the same compression the eye applies, so midtones
+/// keep their structure even when highlights dominate the tone-mapped range.
+fn structure_levels(display: &Framebuffer, hdr: &Framebuffer, log_luminance: bool) -> Vec<f64> {
+    if !log_luminance {
+        return display.pixels.iter().map(|c| c.luminance()).collect();
+    }
+
+    const FLOOR: f64 = 1e-4;
+    let logs: Vec<f64> = hdr
+        .pixels
+        .iter()
+        .map(|c| (c.luminance().max(0.0) + FLOOR).ln())
+        .collect();
+
+    let mut sorted: Vec<f64> = logs.iter().copied().filter(|l| l.is_finite()).collect();
+    if sorted.is_empty() {
+        return vec![0.0; logs.len()];
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let lo = sorted[(sorted.len() - 1) / 100];
+    let hi = sorted[(sorted.len() - 1) * 99 / 100];
+    let range = hi - lo;
+    if range < 1e-6 {
+        return vec![0.5; logs.len()];
+    }
+
+    logs.iter()
+        .map(|l| ((l - lo) / range).clamp(0.0, 1.0))
+        .collect()
+}
+
+fn cells_truecolor(fb: &Framebuffer) -> Vec<Cell> {
+    let mut cells = Vec::with_capacity(fb.pixels.len());
     for y in 0..fb.height {
         for x in 0..fb.width {
-            let (r, g, b) = fb.get(x, y).to_rgb8();
-            let _ = write!(out, "{}", "█".with(style::Color::Rgb { r, g, b }));
+            cells.push(Cell {
+                glyph: '█',
+                fg: Some(fb.get(x, y).to_rgb8()),
+                bg: None,
+            });
         }
-        let _ = writeln!(out);
     }
+    cells
 }
 
-fn display_halfblock(out: &mut impl Write, fb: &Framebuffer) {
+fn cells_halfblock(fb: &Framebuffer) -> Vec<Cell> {
     let rows = fb.height / 2;
+    let mut cells = Vec::with_capacity((rows * fb.width) as usize);
     for row in 0..rows {
         for x in 0..fb.width {
-            let (tr, tg, tb) = fb.get(x, row * 2).to_rgb8();
-            let (br, bg, bb) = fb.get(x, row * 2 + 1).to_rgb8();
-            let _ = write!(
-                out,
-                "{}",
-                "▀"
-                    .with(style::Color::Rgb {
-                        r: tr,
-                        g: tg,
-                        b: tb
-                    })
-                    .on(style::Color::Rgb {
-                        r: br,
-                        g: bg,
-                        b: bb
-                    })
-            );
+            cells.push(Cell {
+                glyph: '▀',
+                fg: Some(fb.get(x, row * 2).to_rgb8()),
+                bg: Some(fb.get(x, row * 2 + 1).to_rgb8()),
+            });
         }
-        let _ = writeln!(out);
     }
+    cells
 }
 
-fn display_ascii(out: &mut impl Write, fb: &Framebuffer) {
+fn cells_ascii(fb: &Framebuffer, levels: &[f64]) -> Vec<Cell> {
     const RAMP: &[u8] = b" .:-=+*#%@";
+    let mut cells = Vec::with_capacity(fb.pixels.len());
     for y in 0..fb.height {
         for x in 0..fb.width {
-            let c = fb.get(x, y);
-            let lum = 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z;
+            let lum = levels[(y * fb.width + x) as usize];
             let idx = (lum.clamp(0.0, 0.999) * RAMP.len() as f64) as usize;
-            let _ = write!(out, "{}", RAMP[idx] as char);
+            cells.push(Cell {
+                glyph: RAMP[idx] as char,
+                fg: None,
+                bg: None,
+            });
         }
-        let _ = writeln!(out);
     }
+    cells
 }
 
 /// Braille pattern rendering — each Unicode braille char (U+2800..U+28FF) encodes
@@ -404,12 +2035,13 @@ fn display_ascii(out: &mut impl Write, fb: &Framebuffer) {
 ///   │ 2 5 │    Bit 7   → dot 7
 ///   │ 6 7 │
 ///   └───┘
-/// Renders framebuffer using Unicode braille characters for maximum terminal resolution.
-fn display_braille(out: &mut impl Write, fb: &Framebuffer) {
+/// Encodes the framebuffer as Unicode braille characters for maximum terminal resolution.
+fn cells_braille(fb: &Framebuffer, levels: &[f64]) -> Vec<Cell> {
     let cell_w = 2u32;
     let cell_h = 4u32;
     let cols = fb.width / cell_w;
     let rows = fb.height / cell_h;
+    let mut cells = Vec::with_capacity((rows * cols) as usize);
 
     for row in 0..rows {
         for col in 0..cols {
@@ -436,8 +2068,7 @@ fn display_braille(out: &mut impl Write, fb: &Framebuffer) {
                 let py = by + dy;
                 if px < fb.width && py < fb.height {
                     let c = fb.get(px, py);
-                    let lum = 0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z;
-                    if lum > 0.15 {
+                    if levels[(py * fb.width + px) as usize] > 0.15 {
                         pattern |= 1 << bit;
                         avg_color += c;
                         lit_count += 1;
@@ -450,13 +2081,118 @@ fn display_braille(out: &mut impl Write, fb: &Framebuffer) {
             }
 
             let braille_char = char::from_u32(0x2800 + pattern as u32).unwrap_or(' ');
-            let (r, g, b) = avg_color.to_rgb8();
-            let _ = write!(
-                out,
-                "{}",
-                braille_char.to_string().with(style::Color::Rgb { r, g, b })
-            );
+            cells.push(Cell {
+                glyph: braille_char,
+                fg: Some(avg_color.to_rgb8()),
+                bg: None,
+            });
         }
-        let _ = writeln!(out);
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resampling_a_constant_buffer_keeps_its_color() {
+        let color = Color::new(0.2, 0.7, 1.3);
+        let mut src = Framebuffer::new(7, 5);
+        src.pixels.fill(color);
+        for filter in [ResampleFilter::Box, ResampleFilter::Bilinear] {
+            for (w, h) in [(3, 2), (4, 3), (1, 1), (10, 9)] {
+                let out = src.resample(w, h, filter);
+                assert_eq!((out.width, out.height), (w, h));
+                assert_eq!(out.pixels.len(), (w * h) as usize);
+                for p in &out.pixels {
+                    assert!(
+                        (*p - color).length() < 1e-12,
+                        "{filter:?} 7×5 → {w}×{h}: {p:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dithered_and_plain_quantization_round_alike() {
+        for v in [0.0, 0.3, 0.5, 100.2 / 255.0, 200.6 / 255.0, 1.0, 1.7] {
+            let pixel = Framebuffer {
+                width: 1,
+                height: 1,
+                pixels: vec![Color::new(v, v, v)],
+            };
+            let expected = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            assert_eq!(pixel.quantize(false), vec![[expected; 3]], "{v}");
+            assert_eq!(pixel.quantize(true), vec![[expected; 3]], "{v}");
+        }
+    }
+
+    #[test]
+    fn film_coordinates_stay_finite_for_one_pixel_frames() {
+        assert_eq!(film_uv(0.5, 0.5, 1, 1), (0.5, 0.5));
+        assert_eq!(film_uv(4.0, 0.0, 5, 1), (1.0, 0.0));
+        assert_eq!(film_uv(2.0, 3.0, 5, 7), (0.5, 0.5));
+    }
+
+    #[test]
+    fn merged_partials_match_a_single_render() {
+        let (world, camera, sky, _, lights) =
+            crate::presets::build_world(crate::presets::ScenePreset::Minimal.build(), 1);
+        let config = |first_sample| RenderConfig {
+            width: 12,
+            height: 8,
+            samples_per_pixel: 4,
+            max_bounces: 4,
+            seed: Some(11),
+            first_sample,
+            threads: 1,
+            ..Default::default()
+        };
+        let render = |config: &RenderConfig, passes| {
+            let tracer = PathTracer {
+                scene: &world,
+                config,
+                camera: &camera,
+                sky,
+                sun: None,
+                lights: &lights,
+                irradiance: None,
+            };
+            tracer.render_progressive(passes, None, None).0
+        };
+        let single = render(&config(0), 2);
+        assert!(single.pixels.iter().any(|p| p.length() > 0.0));
+
+        let mut paths = Vec::new();
+        for first_sample in [0, 4] {
+            let fb = render(&config(first_sample), 1);
+            let name = format!("photon-{}-{first_sample}.photon", std::process::id());
+            let path = std::env::temp_dir().join(name);
+            let path = path.to_string_lossy().into_owned();
+            fb.write_accumulation(&path, 4).unwrap();
+            paths.push(path);
+        }
+        let merged = Framebuffer::merge_accumulations(&paths);
+        paths
+            .iter()
+            .for_each(|path| std::fs::remove_file(path).unwrap());
+        let (merged, samples) = merged.unwrap();
+        assert_eq!(samples, 8);
+        for (m, s) in merged.pixels.iter().zip(&single.pixels) {
+            assert!((*m - *s).length() < 1e-9, "{m:?} vs {s:?}");
+        }
+    }
+
+    #[test]
+    fn sky_sun_replaces_the_physical_sun_disk() {
+        let toward_sun = Vec3::new(0.0, 1.0, 1.0).normalized();
+        let sky = PhysicalSky::new(toward_sun, 3.0);
+        assert!((sky.sample(toward_sun) - sky.sun_color()).length() < 1e-12);
+        let without = sky.without_sun_disk();
+        let bare = without.sample(toward_sun);
+        assert!((bare - sky.sky_color(toward_sun)).length() < 1e-12);
+        assert!(bare.luminance() < sky.sun_color().luminance());
     }
 }
@@ -0,0 +1,253 @@
+use rand::rngs::SmallRng;
+use rand::{Rng, RngCore, SeedableRng};
+
+// ─── Sampler Kinds ──────────────────────────────────────────────────────────
+
+/// Where the integrator's per-sample random numbers come from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplerKind {
+    /// Independent pseudorandom numbers (white noise).
+    Random,
+    /// The Halton sequence, radical inverses in the first primes.
+    Halton,
+    /// The Sobol sequence, with Joe–Kuo direction numbers.
+    Sobol,
+}
+
+/// Leading draws of each sample taken from the low-discrepancy sequence:
+/// the pixel jitter, lens and first bounces. Later draws fall back to the
+/// pixel's pseudorandom generator, where the sequences' higher dimensions
+/// would bring little.
+const QMC_DIMENSIONS: usize = 16;
+/// Draws of the primary ray: pixel jitter (2) and lens position (2).
+const CAMERA_DIMENSIONS: usize = 4;
+/// Draws reserved per bounce: the material's own sample, the strategy
+/// choice, and the light pick and its position. Each bounce starts at a
+/// fixed dimension, so a branch that draws less does not shift the
+/// dimensions of later bounces.
+const BOUNCE_DIMENSIONS: usize = 6;
+
+// ─── Halton ─────────────────────────────────────────────────────────────────
+
+const PRIMES: [u32; QMC_DIMENSIONS] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+/// `index` with its base-`base` digits mirrored about the radix point.
+fn radical_inverse(base: u32, mut index: u32) -> f64 {
+    let inv_base = 1.0 / f64::from(base);
+    let (mut result, mut scale) = (0.0, inv_base);
+    while index > 0 {
+        result += f64::from(index % base) * scale;
+        index /= base;
+        scale *= inv_base;
+    }
+    result
+}
+
+// ─── Sobol ──────────────────────────────────────────────────────────────────
+
+/// Degree `s`, inner coefficients `a` and initial direction numbers `m` of
+/// the primitive polynomials for Sobol dimensions 2–16 (Joe & Kuo 2008,
+/// new-joe-kuo-6.21201). Dimension 1 is the van der Corput sequence.
+const SOBOL_POLYNOMIALS: [(usize, u32, [u32; 6]); QMC_DIMENSIONS - 1] = [
+    (1, 0, [1, 0, 0, 0, 0, 0]),
+    (2, 1, [1, 3, 0, 0, 0, 0]),
+    (3, 1, [1, 3, 1, 0, 0, 0]),
+    (3, 2, [1, 1, 1, 0, 0, 0]),
+    (4, 1, [1, 1, 3, 3, 0, 0]),
+    (4, 4, [1, 3, 5, 13, 0, 0]),
+    (5, 2, [1, 1, 5, 5, 17, 0]),
+    (5, 4, [1, 1, 5, 5, 5, 0]),
+    (5, 7, [1, 1, 7, 11, 19, 0]),
+    (5, 11, [1, 1, 5, 1, 1, 0]),
+    (5, 13, [1, 1, 1, 3, 11, 0]),
+    (5, 14, [1, 3, 5, 5, 31, 0]),
+    (6, 1, [1, 3, 3, 9, 7, 49]),
+    (6, 13, [1, 1, 1, 15, 21, 21]),
+    (6, 16, [1, 3, 1, 13, 27, 49]),
+];
+
+/// Expands the polynomials into 32 direction numbers per dimension with
+/// the Sobol recurrence
+///   v_i = v_{i−s} ⊕ (v_{i−s} >> s) ⊕ a_1·v_{i−1} ⊕ … ⊕ a_{s−1}·v_{i−s+1}
+const fn sobol_directions() -> [[u32; 32]; QMC_DIMENSIONS] {
+    let mut v = [[0u32; 32]; QMC_DIMENSIONS];
+    let mut i = 0;
+    while i < 32 {
+        v[0][i] = 1 << (31 - i);
+        i += 1;
+    }
+    let mut d = 1;
+    while d < QMC_DIMENSIONS {
+        let (s, a, m) = SOBOL_POLYNOMIALS[d - 1];
+        let mut i = 0;
+        while i < 32 {
+            v[d][i] = if i < s {
+                m[i] << (31 - i)
+            } else {
+                let mut x = v[d][i - s] ^ (v[d][i - s] >> s);
+                let mut k = 1;
+                while k < s {
+                    x ^= ((a >> (s - 1 - k)) & 1) * v[d][i - k];
+                    k += 1;
+                }
+                x
+            };
+            i += 1;
+        }
+        d += 1;
+    }
+    v
+}
+
+const SOBOL_DIRECTIONS: [[u32; 32]; QMC_DIMENSIONS] = sobol_directions();
+
+/// Coordinate `dimension` of Sobol point `index`.
+fn sobol(dimension: usize, mut index: u32) -> f64 {
+    let mut x = 0;
+    let mut bit = 0;
+    while index > 0 {
+        if index & 1 == 1 {
+            x ^= SOBOL_DIRECTIONS[dimension][bit];
+        }
+        index >>= 1;
+        bit += 1;
+    }
+    f64::from(x) / 4_294_967_296.0
+}
+
+// ─── Sampler ────────────────────────────────────────────────────────────────
+
+/// Per-pixel random source for the integrator. With a low-discrepancy kind,
+/// the first `QMC_DIMENSIONS` draws of each sample are the coordinates of
+/// that sample's point in the sequence, shifted modulo 1 by a random vector
+/// fixed per pixel (Cranley–Patterson rotation). Sample indices are global
+/// to the render, so progressive passes and `--sample-range` partials that
+/// share the shift seed continue one sequence. The shift decorrelates
+/// neighbouring pixels, and since every shifted point is exactly uniform and
+/// no coordinate is handed out twice per sample, the estimate stays
+/// unbiased however the draws are consumed. The camera and each bounce own
+/// a fixed block of dimensions; draws past the end of a block come from
+/// the pseudorandom generator.
+///
+/// The random kind passes the pixel generator through untouched.
+pub struct Sampler {
+    rng: SmallRng,
+    kind: SamplerKind,
+    shift: [f64; QMC_DIMENSIONS],
+    index: u32,
+    dimension: usize,
+    /// End of the current block of dimensions.
+    block_end: usize,
+}
+
+impl Sampler {
+    /// `rng` supplies the pseudorandom draws; the rotation is drawn from
+    /// `shift_seed`, which the random kind ignores.
+    pub fn new(rng: SmallRng, kind: SamplerKind, shift_seed: u64) -> Self {
+        let mut shift = [0.0; QMC_DIMENSIONS];
+        if kind != SamplerKind::Random {
+            let mut shift_rng = SmallRng::seed_from_u64(shift_seed);
+            shift.iter_mut().for_each(|s| *s = shift_rng.gen());
+        }
+        Self {
+            rng,
+            kind,
+            shift,
+            index: 0,
+            dimension: QMC_DIMENSIONS,
+            block_end: QMC_DIMENSIONS,
+        }
+    }
+
+    /// Begins sample `index` of the pixel at the camera's dimensions.
+    pub fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+        self.block_end = CAMERA_DIMENSIONS;
+    }
+
+    /// Begins bounce `depth` of the current sample, at its reserved
+    /// dimensions.
+    pub fn start_bounce(&mut self, depth: u32) {
+        let first = CAMERA_DIMENSIONS + depth as usize * BOUNCE_DIMENSIONS;
+        self.dimension = first.min(QMC_DIMENSIONS);
+        self.block_end = (first + BOUNCE_DIMENSIONS).min(QMC_DIMENSIONS);
+    }
+
+    /// The next sequence coordinate, or `None` once the current block is
+    /// used up (and always for the random kind).
+    fn next_qmc(&mut self) -> Option<f64> {
+        let d = self.dimension;
+        let value = match self.kind {
+            _ if d >= self.block_end => return None,
+            SamplerKind::Random => return None,
+            SamplerKind::Halton => radical_inverse(PRIMES[d], self.index),
+            SamplerKind::Sobol => sobol(d, self.index),
+        };
+        self.dimension += 1;
+        Some((value + self.shift[d]).fract())
+    }
+}
+
+impl RngCore for Sampler {
+    fn next_u32(&mut self) -> u32 {
+        match self.next_qmc() {
+            Some(u) => (u * 4_294_967_296.0) as u32,
+            None => self.rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self.next_qmc() {
+            Some(u) => (u * 18_446_744_073_709_551_616.0) as u64,
+            None => self.rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radical_inverse_mirrors_digits() {
+        let base2: Vec<f64> = (1..=4).map(|i| radical_inverse(2, i)).collect();
+        assert_eq!(base2, [0.5, 0.25, 0.75, 0.125]);
+        assert!((radical_inverse(3, 1) - 1.0 / 3.0).abs() < 1e-12);
+        assert!((radical_inverse(3, 5) - 7.0 / 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sobol_first_points_match_reference() {
+        let first: Vec<f64> = (0..4).map(|i| sobol(0, i)).collect();
+        assert_eq!(first, [0.0, 0.5, 0.25, 0.75]);
+        let second: Vec<f64> = (0..4).map(|i| sobol(1, i)).collect();
+        assert_eq!(second, [0.0, 0.5, 0.75, 0.25]);
+    }
+
+    #[test]
+    fn shifted_samples_stay_in_unit_interval() {
+        for kind in [SamplerKind::Halton, SamplerKind::Sobol] {
+            for pixel in 0..16 {
+                let mut sampler = Sampler::new(SmallRng::seed_from_u64(pixel), kind, pixel);
+                for index in 0..256 {
+                    sampler.start_sample(index);
+                    for depth in 0..3 {
+                        while let Some(u) = sampler.next_qmc() {
+                            assert!((0.0..1.0).contains(&u), "{u} out of range");
+                        }
+                        sampler.start_bounce(depth);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::math::*;
 use rand::Rng;
 use std::cmp::Ordering;
+use std::sync::Arc;
 
 // ─── Hit Record ─────────────────────────────────────────────────────────────
 
@@ -10,6 +11,8 @@ pub struct HitRecord<'a> {
     pub t: f64,
     pub front_face: bool,
     pub material: &'a dyn Material,
+    /// Name of the object that was hit, if it was wrapped in `Named`.
+    pub object: Option<&'a str>,
 }
 
 impl<'a> HitRecord<'a> {
@@ -36,24 +39,148 @@ pub trait Material: Send + Sync {
     fn emitted(&self) -> Color {
         Color::zero()
     }
+
+    /// Solid-angle density with which `scatter` would produce `scattered`.
+    /// Materials that override this promise `attenuation · pdf = BRDF · cos θ`
+    /// for any direction, which lets the integrator draw directions from
+    /// other strategies (path guiding, light sampling) and reweight them.
+    /// The default of zero opts a material out of those strategies.
+    fn scattering_pdf(&self, _ray: &Ray, _hit: &HitRecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    /// Importance hint in [0, 1] scaling how strongly path guiding may steer
+    /// bounces off this material away from its own BRDF sampling.
+    fn guiding_importance(&self) -> f64 {
+        1.0
+    }
+
+    /// Whether surfaces with this material should be registered as lights.
+    fn is_emissive(&self) -> bool {
+        false
+    }
+
+    /// Whether `scatter` follows a delta distribution (a perfect mirror or
+    /// refraction). Such bounces have no BRDF density, so light sampling
+    /// can never hit their one direction and must be skipped.
+    fn is_specular(&self) -> bool {
+        false
+    }
+
+    /// Material type, for scene statistics.
+    fn label(&self) -> &'static str {
+        "Custom"
+    }
+}
+
+/// Cosine-weighted hemisphere density cos θ / π about `normal`, shared by
+/// all the diffuse materials.
+fn cosine_pdf(normal: Vec3, direction: Vec3) -> f64 {
+    let cosine = normal.dot(direction.normalized());
+    if cosine > 0.0 {
+        cosine / std::f64::consts::PI
+    } else {
+        0.0
+    }
+}
+
+/// Path-guiding importance of the procedural matte materials. They mostly
+/// serve as ground planes and props open to the sky, where cosine sampling
+/// already fits the incoming light and a sparse guide adds more noise than
+/// it removes.
+const GUIDING_IMPORTANCE_OPEN: f64 = 0.5;
+
+// ─── Textures ───────────────────────────────────────────────────────────────
+
+/// A spatially varying albedo, evaluated at the hit point. Lambertian and
+/// Metal take any texture, so the procedural patterns below work as the
+/// color of a mirror or glossy surface as well as a matte one.
+pub trait Texture: Send + Sync {
+    fn value(&self, hit: &HitRecord) -> Color;
+}
+
+impl Texture for Color {
+    fn value(&self, _hit: &HitRecord) -> Color {
+        *self
+    }
+}
+
+/// A flat pattern over (s, t) with a period of one unit in each direction,
+/// placed on surfaces by a projection such as `Triplanar`.
+pub trait Pattern: Send + Sync {
+    fn at(&self, s: f64, t: f64) -> Color;
+}
+
+/// Square tiles separated by grout lines `grout_width` wide (as a fraction
+/// of the tile).
+pub struct TilePattern {
+    pub tile: Color,
+    pub grout: Color,
+    pub grout_width: f64,
+}
+
+impl Pattern for TilePattern {
+    fn at(&self, s: f64, t: f64) -> Color {
+        let half = 0.5 * self.grout_width;
+        let in_grout = |c: f64| {
+            let f = c.rem_euclid(1.0);
+            f < half || f > 1.0 - half
+        };
+        if in_grout(s) || in_grout(t) {
+            self.grout
+        } else {
+            self.tile
+        }
+    }
+}
+
+/// Projects a pattern onto any surface along the world axis closest to the
+/// surface normal, using world coordinates divided by `tile_size`. Unlike a
+/// shape's own parametric coordinates, this repeats the pattern at a fixed
+/// world scale, so a large floor gets many tiles instead of one stretched
+/// tile.
+pub struct Triplanar<P: Pattern> {
+    pub pattern: P,
+    pub tile_size: f64,
+}
+
+impl<P: Pattern> Triplanar<P> {
+    pub fn new(pattern: P, tile_size: f64) -> Self {
+        Self { pattern, tile_size }
+    }
+}
+
+impl<P: Pattern> Texture for Triplanar<P> {
+    fn value(&self, hit: &HitRecord) -> Color {
+        let n = hit.normal;
+        let p = hit.point / self.tile_size;
+        let (ax, ay, az) = (n.x.abs(), n.y.abs(), n.z.abs());
+        if ay >= ax && ay >= az {
+            self.pattern.at(p.x, p.z)
+        } else if ax >= az {
+            self.pattern.at(p.z, p.y)
+        } else {
+            self.pattern.at(p.x, p.y)
+        }
+    }
 }
 
 // ─── Lambertian (Diffuse) ───────────────────────────────────────────────────
 
-pub struct Lambertian {
-    pub albedo: Color,
+pub struct Lambertian<T: Texture = Color> {
+    pub albedo: T,
 }
 
-impl Lambertian {
-    pub const fn new(albedo: Color) -> Self {
+impl<T: Texture> Lambertian<T> {
+    pub const fn new(albedo: T) -> Self {
         Self { albedo }
     }
 }
 
-impl Material for Lambertian {
+impl<T: Texture> Material for Lambertian<T> {
     fn scatter(
         &self,
-        _ray: &Ray,
+        ray: &Ray,
         hit: &HitRecord,
         rng: &mut dyn rand::RngCore,
     ) -> Option<(Ray, Color)> {
@@ -61,19 +188,31 @@ impl Material for Lambertian {
         if scatter_dir.near_zero() {
             scatter_dir = hit.normal;
         }
-        Some((Ray::new(hit.point, scatter_dir), self.albedo))
+        Some((
+            Ray::with_time(hit.point, scatter_dir, ray.time),
+            self.albedo.value(hit),
+        ))
+    }
+
+    fn scattering_pdf(&self, _ray: &Ray, hit: &HitRecord, scattered: &Ray) -> f64 {
+        cosine_pdf(hit.normal, scattered.direction)
+    }
+
+    fn label(&self) -> &'static str {
+        "Lambertian"
     }
 }
 
 // ─── Metal (Specular) ───────────────────────────────────────────────────────
 
-pub struct Metal {
-    pub albedo: Color,
+/// A specular reflector; `fuzz` roughens the mirror into a glossy lobe.
+pub struct Metal<T: Texture = Color> {
+    pub albedo: T,
     pub fuzz: f64,
 }
 
-impl Metal {
-    pub fn new(albedo: Color, fuzz: f64) -> Self {
+impl<T: Texture> Metal<T> {
+    pub fn new(albedo: T, fuzz: f64) -> Self {
         Self {
             albedo,
             fuzz: fuzz.min(1.0),
@@ -81,7 +220,7 @@ impl Metal {
     }
 }
 
-impl Material for Metal {
+impl<T: Texture> Material for Metal<T> {
     fn scatter(
         &self,
         ray: &Ray,
@@ -89,16 +228,29 @@ impl Material for Metal {
         rng: &mut dyn rand::RngCore,
     ) -> Option<(Ray, Color)> {
         let reflected = ray.direction.normalized().reflect(hit.normal);
-        let scattered = Ray::new(
+        let scattered = Ray::with_time(
             hit.point,
-            reflected + Vec3::random_in_unit_sphere(rng) * self.fuzz,
+            // `fuzz` is public, so bound it here as well as in `new`: a larger
+            // one could point the lobe anywhere. The fuzzed sum is up to twice
+            // unit length, which would stretch the ray epsilon (measured in
+            // units of t) along it, so bound that too.
+            (reflected + Vec3::random_in_unit_sphere(rng) * self.fuzz.min(1.0)).clamp_length(1.0),
+            ray.time,
         );
         if scattered.direction.dot(hit.normal) > 0.0 {
-            Some((scattered, self.albedo))
+            Some((scattered, self.albedo.value(hit)))
         } else {
             None
         }
     }
+
+    fn is_specular(&self) -> bool {
+        self.fuzz == 0.0
+    }
+
+    fn label(&self) -> &'static str {
+        "Metal"
+    }
 }
 
 // ─── Dielectric (Glass) ────────────────────────────────────────────────────
@@ -112,10 +264,19 @@ impl Dielectric {
         Self { ior }
     }
 
-    /// Schlick's approximation for Fresnel reflectance at grazing angles.
-    fn schlick_reflectance(cosine: f64, ref_idx: f64) -> f64 {
-        let r0 = ((1.0 - ref_idx) / (1.0 + ref_idx)).powi(2);
-        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+    /// Exact Fresnel reflectance for unpolarized light arriving at `cos_i`
+    /// from the side with relative index `eta_ratio` (n_incident / n_transmitted):
+    /// the mean of the s- and p-polarized terms, or 1 under total internal
+    /// reflection.
+    pub fn fresnel_reflectance(cos_i: f64, eta_ratio: f64) -> f64 {
+        let sin2_t = eta_ratio * eta_ratio * (1.0 - cos_i * cos_i);
+        if sin2_t >= 1.0 {
+            return 1.0;
+        }
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let r_s = (eta_ratio * cos_i - cos_t) / (eta_ratio * cos_i + cos_t);
+        let r_p = (cos_i - eta_ratio * cos_t) / (cos_i + eta_ratio * cos_t);
+        0.5 * (r_s * r_s + r_p * r_p)
     }
 }
 
@@ -132,20 +293,110 @@ impl Material for Dielectric {
             self.ior
         };
         let unit_dir = ray.direction.normalized();
-        let cos_theta = (-unit_dir).dot(hit.normal).min(1.0);
-        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let cos_theta = (-unit_dir).dot(hit.normal).clamp(0.0, 1.0);
+        let reflectance = Self::fresnel_reflectance(cos_theta, eta_ratio);
+
+        // The branch is picked with probability `p_reflect` and its Fresnel
+        // weight divided by that probability, so the split stays unbiased
+        // for any choice of `p_reflect`. Choosing the Fresnel term itself
+        // makes both weights exactly 1.
+        let p_reflect = reflectance;
+        let refracted = unit_dir.refract(hit.normal, eta_ratio);
+        let (direction, weight) = match refracted {
+            Some(refracted) if p_reflect < 1.0 && rng.gen::<f64>() >= p_reflect => {
+                (refracted, (1.0 - reflectance) / (1.0 - p_reflect))
+            }
+            _ if p_reflect > 0.0 => (unit_dir.reflect(hit.normal), reflectance / p_reflect),
+            // Numerically at the critical angle: treat as total reflection.
+            _ => (unit_dir.reflect(hit.normal), 1.0),
+        };
+
+        Some((
+            Ray::with_time(hit.point, direction, ray.time),
+            Color::ones() * weight,
+        ))
+    }
+
+    fn is_specular(&self) -> bool {
+        true
+    }
+
+    fn label(&self) -> &'static str {
+        "Dielectric"
+    }
+}
+
+// ─── Thin Translucent (Paper / Leaf) ────────────────────────────────────────
+
+/// A thin two-sided diffuse sheet such as paper or a leaf. Each bounce either
+/// reflects diffusely back into the hemisphere of the hit side or transmits
+/// diffusely into the opposite hemisphere — no refraction, no thickness.
+///
+/// The branch is chosen with probability proportional to the luminance of
+/// `reflectance` vs. `transmittance`, and the chosen color is divided by that
+/// probability so the estimator stays unbiased.
+pub struct ThinTranslucent {
+    pub reflectance: Color,
+    pub transmittance: Color,
+}
+
+impl ThinTranslucent {
+    pub const fn new(reflectance: Color, transmittance: Color) -> Self {
+        Self {
+            reflectance,
+            transmittance,
+        }
+    }
+
+    /// Probability of the transmitting branch, or `None` for a black sheet.
+    fn transmit_probability(&self) -> Option<f64> {
+        let r = self.reflectance.luminance().max(0.0);
+        let t = self.transmittance.luminance().max(0.0);
+        (r + t > 0.0).then(|| t / (r + t))
+    }
+}
+
+impl Material for ThinTranslucent {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn rand::RngCore,
+    ) -> Option<(Ray, Color)> {
+        let p_transmit = self.transmit_probability()?;
 
-        let cannot_refract = eta_ratio * sin_theta > 1.0;
-        let direction =
-            if cannot_refract || Self::schlick_reflectance(cos_theta, eta_ratio) > rng.gen() {
-                unit_dir.reflect(hit.normal)
-            } else {
-                unit_dir
-                    .refract(hit.normal, eta_ratio)
-                    .unwrap_or_else(|| unit_dir.reflect(hit.normal))
-            };
+        let (side, attenuation) = if rng.gen::<f64>() < p_transmit {
+            (-hit.normal, self.transmittance / p_transmit)
+        } else {
+            (hit.normal, self.reflectance / (1.0 - p_transmit))
+        };
+        let mut scatter_dir = side + Vec3::random_unit_vector(rng);
+        if scatter_dir.near_zero() {
+            scatter_dir = side;
+        }
+        Some((
+            Ray::with_time(hit.point, scatter_dir, ray.time),
+            attenuation,
+        ))
+    }
 
-        Some((Ray::new(hit.point, direction), Color::ones()))
+    /// Cosine-weighted on whichever hemisphere `scattered` lies in, scaled
+    /// by the probability of the branch that samples it, so the density
+    /// integrates to one over the sphere. Each side then meets the BRDF
+    /// contract with its own branch's attenuation; a light sample landing
+    /// on the other side is weighed with the sampled branch's color, which
+    /// is exact in luminance.
+    fn scattering_pdf(&self, _ray: &Ray, hit: &HitRecord, scattered: &Ray) -> f64 {
+        let Some(p_transmit) = self.transmit_probability() else {
+            return 0.0;
+        };
+        let reflect = cosine_pdf(hit.normal, scattered.direction);
+        let transmit = cosine_pdf(-hit.normal, scattered.direction);
+        (1.0 - p_transmit) * reflect + p_transmit * transmit
+    }
+
+    fn label(&self) -> &'static str {
+        "ThinTranslucent"
     }
 }
 
@@ -178,6 +429,19 @@ impl Material for Emissive {
     fn emitted(&self) -> Color {
         self.emit_color * self.intensity
     }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
+
+    /// Emitters end the path, so there is no bounce to guide.
+    fn guiding_importance(&self) -> f64 {
+        0.0
+    }
+
+    fn label(&self) -> &'static str {
+        "Emissive"
+    }
 }
 
 // ─── Checkerboard Material ──────────────────────────────────────────────────
@@ -196,11 +460,14 @@ impl Checkerboard {
             scale,
         }
     }
+}
 
-    fn pattern_at(&self, point: Point3) -> Color {
-        let sines = (self.scale * point.x).sin()
-            * (self.scale * point.y).sin()
-            * (self.scale * point.z).sin();
+/// The checker pattern alone, in world space, for use as another
+/// material's albedo (e.g. `Metal::new(Checkerboard::new(..), 0.2)`).
+impl Texture for Checkerboard {
+    fn value(&self, hit: &HitRecord) -> Color {
+        let p = hit.point;
+        let sines = (self.scale * p.x).sin() * (self.scale * p.y).sin() * (self.scale * p.z).sin();
         if sines < 0.0 {
             self.color_a
         } else {
@@ -212,7 +479,7 @@ impl Checkerboard {
 impl Material for Checkerboard {
     fn scatter(
         &self,
-        _ray: &Ray,
+        ray: &Ray,
         hit: &HitRecord,
         rng: &mut dyn rand::RngCore,
     ) -> Option<(Ray, Color)> {
@@ -220,7 +487,22 @@ impl Material for Checkerboard {
         if scatter_dir.near_zero() {
             scatter_dir = hit.normal;
         }
-        Some((Ray::new(hit.point, scatter_dir), self.pattern_at(hit.point)))
+        Some((
+            Ray::with_time(hit.point, scatter_dir, ray.time),
+            self.value(hit),
+        ))
+    }
+
+    fn scattering_pdf(&self, _ray: &Ray, hit: &HitRecord, scattered: &Ray) -> f64 {
+        cosine_pdf(hit.normal, scattered.direction)
+    }
+
+    fn guiding_importance(&self) -> f64 {
+        GUIDING_IMPORTANCE_OPEN
+    }
+
+    fn label(&self) -> &'static str {
+        "Checkerboard"
     }
 }
 
@@ -245,10 +527,18 @@ impl GradientMaterial {
     }
 }
 
+/// The normal-driven gradient alone, for use as another material's albedo.
+impl Texture for GradientMaterial {
+    fn value(&self, hit: &HitRecord) -> Color {
+        let t = (hit.normal.dot(self.axis) * 0.5 + 0.5).clamp(0.0, 1.0);
+        self.color_a.lerp(self.color_b, t)
+    }
+}
+
 impl Material for GradientMaterial {
     fn scatter(
         &self,
-        _ray: &Ray,
+        ray: &Ray,
         hit: &HitRecord,
         rng: &mut dyn rand::RngCore,
     ) -> Option<(Ray, Color)> {
@@ -256,17 +546,293 @@ impl Material for GradientMaterial {
         if scatter_dir.near_zero() {
             scatter_dir = hit.normal;
         }
-        let t = (hit.normal.dot(self.axis) * 0.5 + 0.5).clamp(0.0, 1.0);
-        let albedo = self.color_a.lerp(self.color_b, t);
-        Some((Ray::new(hit.point, scatter_dir), albedo))
+        Some((
+            Ray::with_time(hit.point, scatter_dir, ray.time),
+            self.value(hit),
+        ))
+    }
+
+    fn scattering_pdf(&self, _ray: &Ray, hit: &HitRecord, scattered: &Ray) -> f64 {
+        cosine_pdf(hit.normal, scattered.direction)
+    }
+
+    fn guiding_importance(&self) -> f64 {
+        GUIDING_IMPORTANCE_OPEN
+    }
+
+    fn label(&self) -> &'static str {
+        "Gradient"
     }
 }
 
 // ─── Hittable Trait ─────────────────────────────────────────────────────────
 
+/// Minimum hit distance when a light checks whether a direction reaches it,
+/// at scene scale 1. Shading points never lie on a light (emitters do not
+/// scatter), so only t = 0 needs excluding; the integrator multiplies this
+/// by the scene scale so the margin stays proportionate.
+pub const LIGHT_QUERY_T_MIN: f64 = 1e-9;
+
+/// Bounding-box diagonal of a scene at scale 1: a cube ten units on a side,
+/// the size the ray epsilon and the tolerances here are tuned for.
+pub const REFERENCE_DIAGONAL: f64 = 17.320_508_075_688_775;
+
+/// A scene's size relative to `REFERENCE_DIAGONAL`, from the diagonal of its
+/// bounding box; 1 for an empty box.
+pub fn extent_scale(bbox: &Aabb) -> f64 {
+    let diagonal = (bbox.max - bbox.min).length();
+    if diagonal.is_finite() && diagonal > 0.0 {
+        diagonal / REFERENCE_DIAGONAL
+    } else {
+        1.0
+    }
+}
+
+/// Smallest Möller–Trumbore determinant, relative to the edge and direction
+/// lengths, that counts as a hit; below it the ray runs parallel to the
+/// triangle's plane. Planes, quads and disks apply it to the cosine between
+/// the ray and their normal.
+const PARALLEL_EPSILON: f64 = 1e-8;
+
+/// Whether a ray runs parallel to a flat primitive, given
+/// `denom = direction · normal` for its unit normal. The test is on the
+/// cosine, so it holds however long `direction` is.
+fn runs_parallel(denom: f64, direction: Vec3) -> bool {
+    denom * denom <= PARALLEL_EPSILON * PARALLEL_EPSILON * direction.length_squared()
+}
+
 pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>>;
     fn bounding_box(&self) -> Aabb;
+
+    /// Solid-angle probability density with which `random_toward(origin)`
+    /// produces `direction`. Together with the material's BRDF pdf this is
+    /// what multiple importance sampling weighs light samples against.
+    /// Primitives that cannot be sampled as lights return zero. Hits nearer
+    /// than `t_min` do not count as reaching the primitive.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3, _t_min: f64) -> f64 {
+        0.0
+    }
+
+    /// Samples a (non-normalized) direction from `origin` toward a point on
+    /// this primitive's surface, distributed according to `pdf_value`.
+    fn random_toward(&self, _origin: Point3, _rng: &mut dyn rand::RngCore) -> Vec3 {
+        Vec3::unit_x()
+    }
+
+    /// Whether this primitive is an emitter that `random_toward` can sample.
+    /// Wrappers must forward this so lights are found through them.
+    fn is_emitter(&self) -> bool {
+        false
+    }
+
+    /// Stable identifier for this object, if it has one.
+    fn name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Primitive type, for scene statistics.
+    fn kind(&self) -> &'static str {
+        "Other"
+    }
+
+    /// Surface area in scene units squared; infinite for unbounded shapes.
+    fn surface_area(&self) -> f64 {
+        0.0
+    }
+
+    /// The surface material, for primitives that have a single one.
+    fn material(&self) -> Option<&dyn Material> {
+        None
+    }
+}
+
+/// Shared handles let the same primitive live in both the BVH and the
+/// integrator's light list.
+impl Hittable for Arc<dyn Hittable> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        self.as_ref().hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.as_ref().bounding_box()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, t_min: f64) -> f64 {
+        self.as_ref().pdf_value(origin, direction, t_min)
+    }
+
+    fn random_toward(&self, origin: Point3, rng: &mut dyn rand::RngCore) -> Vec3 {
+        self.as_ref().random_toward(origin, rng)
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.as_ref().is_emitter()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.as_ref().name()
+    }
+
+    fn kind(&self) -> &'static str {
+        self.as_ref().kind()
+    }
+
+    fn surface_area(&self) -> f64 {
+        self.as_ref().surface_area()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.as_ref().material()
+    }
+}
+
+// ─── Lights ─────────────────────────────────────────────────────────────────
+
+/// An entry in the integrator's light list, sampled for next-event estimation.
+pub enum LightHandle {
+    /// An emissive primitive that is also part of the scene geometry.
+    Area(Arc<dyn Hittable>),
+    /// A sun disk in the sky, infinitely far away.
+    Sun(SkySun),
+}
+
+impl LightHandle {
+    pub fn pdf_value(&self, origin: Point3, direction: Vec3, t_min: f64) -> f64 {
+        match self {
+            LightHandle::Area(object) => object.pdf_value(origin, direction, t_min),
+            LightHandle::Sun(sun) => sun.pdf_value(direction),
+        }
+    }
+
+    pub fn random_toward(&self, origin: Point3, rng: &mut dyn rand::RngCore) -> Vec3 {
+        match self {
+            LightHandle::Area(object) => object.random_toward(origin, rng),
+            LightHandle::Sun(sun) => sun.random_direction(rng),
+        }
+    }
+
+    /// The light's object name, or its primitive type if it has none.
+    pub fn label(&self) -> &str {
+        match self {
+            LightHandle::Area(object) => object.name().unwrap_or(object.kind()),
+            LightHandle::Sun(_) => "sun",
+        }
+    }
+}
+
+/// A sun lamp without geometry: a cone of directions in the sky with
+/// constant radiance. Rays that escape the scene inside the cone see the
+/// sun, and as a light it is sampled uniformly over the cone, so nearby
+/// geometry casts shadows whose softness follows the angular size.
+#[derive(Debug, Clone, Copy)]
+pub struct SkySun {
+    direction: Vec3,
+    /// Cosine of the angular radius.
+    cos_max: f64,
+    radiance: Color,
+}
+
+impl SkySun {
+    /// A sun toward `direction` spanning `angular_diameter` degrees. The
+    /// radiance is derived from `irradiance`, the light falling on a surface
+    /// facing the sun, so the angular size changes only the shadow softness.
+    pub fn new(direction: Vec3, angular_diameter: f64, irradiance: Color) -> Self {
+        let cos_max = (angular_diameter.to_radians() / 2.0).cos();
+        let sun = Self {
+            direction: direction.normalized(),
+            cos_max,
+            radiance: Color::zero(),
+        };
+        Self {
+            radiance: irradiance * sun.pdf_cone(),
+            ..sun
+        }
+    }
+
+    /// Uniform density over the cone's solid angle, 2π(1 − cos θmax).
+    fn pdf_cone(&self) -> f64 {
+        1.0 / (2.0 * std::f64::consts::PI * (1.0 - self.cos_max))
+    }
+
+    /// Radiance seen along unit `direction`, if it points into the disk.
+    pub fn radiance_toward(&self, direction: Vec3) -> Option<Color> {
+        (direction.dot(self.direction) >= self.cos_max).then_some(self.radiance)
+    }
+
+    fn pdf_value(&self, direction: Vec3) -> f64 {
+        if direction.normalized().dot(self.direction) >= self.cos_max {
+            self.pdf_cone()
+        } else {
+            0.0
+        }
+    }
+
+    fn random_direction(&self, rng: &mut dyn rand::RngCore) -> Vec3 {
+        let cos_theta = 1.0 - rng.gen::<f64>() * (1.0 - self.cos_max);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let phi = 2.0 * std::f64::consts::PI * rng.gen::<f64>();
+        Onb::from_w(self.direction).local(phi.cos() * sin_theta, phi.sin() * sin_theta, cos_theta)
+    }
+}
+
+// ─── Named Objects ──────────────────────────────────────────────────────────
+
+/// Attaches a name to any object and stamps it on every `HitRecord` it
+/// produces, so the integrator and tooling can tell which object was hit.
+/// Unnamed objects pay nothing beyond the `None` in the record.
+pub struct Named<H: Hittable> {
+    pub name: String,
+    pub object: H,
+}
+
+impl<H: Hittable> Named<H> {
+    pub fn new(name: impl Into<String>, object: H) -> Self {
+        Self {
+            name: name.into(),
+            object,
+        }
+    }
+}
+
+impl<H: Hittable> Hittable for Named<H> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let mut rec = self.object.hit(ray, t_min, t_max)?;
+        rec.object = Some(&self.name);
+        Some(rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.object.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, t_min: f64) -> f64 {
+        self.object.pdf_value(origin, direction, t_min)
+    }
+
+    fn random_toward(&self, origin: Point3, rng: &mut dyn rand::RngCore) -> Vec3 {
+        self.object.random_toward(origin, rng)
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.object.is_emitter()
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn kind(&self) -> &'static str {
+        self.object.kind()
+    }
+
+    fn surface_area(&self) -> f64 {
+        self.object.surface_area()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        self.object.material()
+    }
 }
 
 // ─── Sphere ─────────────────────────────────────────────────────────────────
@@ -275,6 +841,10 @@ pub struct Sphere {
     pub center: Point3,
     pub radius: f64,
     pub material: Box<dyn Material>,
+    /// Displacement per unit of ray time. Time runs from 0 to 1 over a
+    /// frame, so a sphere sweeps `center` → `center + velocity` and an open
+    /// camera shutter blurs it along that path.
+    pub velocity: Vec3,
 }
 
 impl Sphere {
@@ -283,13 +853,26 @@ impl Sphere {
             center,
             radius,
             material: Box::new(material),
+            velocity: Vec3::zero(),
         }
     }
+
+    /// Sets the sphere moving. Light sampling sees it at its time-0
+    /// position, so emitters should stay static.
+    pub fn with_velocity(mut self, velocity: Vec3) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    fn center_at(&self, time: f64) -> Point3 {
+        self.center + self.velocity * time
+    }
 }
 
 impl Hittable for Sphere {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
-        let oc = ray.origin - self.center;
+        let center = self.center_at(ray.time);
+        let oc = ray.origin - center;
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
         let c = oc.length_squared() - self.radius * self.radius;
@@ -309,24 +892,87 @@ impl Hittable for Sphere {
         }
 
         let point = ray.at(root);
-        let outward_normal = (point - self.center) / self.radius;
+        let outward_normal = (point - center) / self.radius;
         let mut rec = HitRecord {
             point,
             normal: outward_normal,
             t: root,
             front_face: true,
             material: self.material.as_ref(),
+            object: None,
         };
         rec.set_face_normal(ray, outward_normal);
         Some(rec)
     }
 
+    /// Covers the whole sweep from time 0 to 1.
     fn bounding_box(&self) -> Aabb {
         let r = Vec3::new(self.radius.abs(), self.radius.abs(), self.radius.abs());
-        Aabb::new(self.center - r, self.center + r)
+        let end = self.center_at(1.0);
+        Aabb::surrounding(
+            &Aabb::new(self.center - r, self.center + r),
+            &Aabb::new(end - r, end + r),
+        )
+    }
+
+    /// Uniform density over the cone of directions subtended by the sphere:
+    ///   pdf = 1 / (2π · (1 − cos θ_max)),  cos θ_max = √(1 − r²/d²)
+    /// From inside the sphere every direction hits it, so the pdf falls back
+    /// to uniform over the full sphere of directions.
+    fn pdf_value(&self, origin: Point3, direction: Vec3, t_min: f64) -> f64 {
+        if self
+            .hit(&Ray::new(origin, direction), t_min, f64::INFINITY)
+            .is_none()
+        {
+            return 0.0;
+        }
+        let dist_sq = (self.center - origin).length_squared();
+        let r_sq = self.radius * self.radius;
+        if dist_sq <= r_sq {
+            return 1.0 / (4.0 * std::f64::consts::PI);
+        }
+        let cos_theta_max = (1.0 - r_sq / dist_sq).sqrt();
+        1.0 / (2.0 * std::f64::consts::PI * (1.0 - cos_theta_max))
+    }
+
+    fn random_toward(&self, origin: Point3, rng: &mut dyn rand::RngCore) -> Vec3 {
+        let to_center = self.center - origin;
+        let dist_sq = to_center.length_squared();
+        let r_sq = self.radius * self.radius;
+        if dist_sq <= r_sq {
+            return Vec3::random_unit_vector(rng);
+        }
+        let cos_theta_max = (1.0 - r_sq / dist_sq).sqrt();
+        let r1: f64 = rng.gen();
+        let r2: f64 = rng.gen();
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * std::f64::consts::PI * r1;
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+        Onb::from_w(to_center).local(phi.cos() * sin_theta, phi.sin() * sin_theta, z)
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn kind(&self) -> &'static str {
+        "Sphere"
+    }
+
+    fn surface_area(&self) -> f64 {
+        4.0 * std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(self.material.as_ref())
     }
 }
 
+/// Minimum thickness of a BVH leaf box at scene scale 1, so flat primitives
+/// (triangles, quads, disks) lying in a coordinate plane still have a usable
+/// slab. The BVH scales it by the extent of the whole scene.
+const FLAT_BOX_PAD: f64 = 2e-4;
+
 // ─── Infinite Plane ─────────────────────────────────────────────────────────
 
 #[allow(dead_code)]
@@ -350,7 +996,7 @@ impl Plane {
 impl Hittable for Plane {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         let denom = ray.direction.dot(self.normal);
-        if denom.abs() < 1e-8 {
+        if runs_parallel(denom, ray.direction) {
             return None;
         }
         let t = (self.point - ray.origin).dot(self.normal) / denom;
@@ -364,6 +1010,7 @@ impl Hittable for Plane {
             t,
             front_face: true,
             material: self.material.as_ref(),
+            object: None,
         };
         rec.set_face_normal(ray, self.normal);
         Some(rec)
@@ -373,6 +1020,18 @@ impl Hittable for Plane {
         let big = 1e4;
         Aabb::new(Point3::new(-big, -big, -big), Point3::new(big, big, big))
     }
+
+    fn kind(&self) -> &'static str {
+        "Plane"
+    }
+
+    fn surface_area(&self) -> f64 {
+        f64::INFINITY
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(self.material.as_ref())
+    }
 }
 
 // ─── Triangle (Möller–Trumbore 1997) ──────────────────────────────────────────
@@ -403,7 +1062,11 @@ impl Hittable for Triangle {
         let edge2 = self.v2 - self.v0;
         let h = ray.direction.cross(edge2);
         let a = edge1.dot(h);
-        if a.abs() < 1e-8 {
+        // `a` scales with the edge lengths and the ray direction, so the
+        // parallel test is relative to them and holds at any scene scale.
+        let scale_sq =
+            edge1.length_squared() * edge2.length_squared() * ray.direction.length_squared();
+        if a * a <= PARALLEL_EPSILON * PARALLEL_EPSILON * scale_sq {
             return None;
         }
 
@@ -433,25 +1096,37 @@ impl Hittable for Triangle {
             t,
             front_face: true,
             material: self.material.as_ref(),
+            object: None,
         };
         rec.set_face_normal(ray, outward_normal);
         Some(rec)
     }
 
     fn bounding_box(&self) -> Aabb {
-        let eps = 1e-4;
         let min = Point3::new(
-            self.v0.x.min(self.v1.x).min(self.v2.x) - eps,
-            self.v0.y.min(self.v1.y).min(self.v2.y) - eps,
-            self.v0.z.min(self.v1.z).min(self.v2.z) - eps,
+            self.v0.x.min(self.v1.x).min(self.v2.x),
+            self.v0.y.min(self.v1.y).min(self.v2.y),
+            self.v0.z.min(self.v1.z).min(self.v2.z),
         );
         let max = Point3::new(
-            self.v0.x.max(self.v1.x).max(self.v2.x) + eps,
-            self.v0.y.max(self.v1.y).max(self.v2.y) + eps,
-            self.v0.z.max(self.v1.z).max(self.v2.z) + eps,
+            self.v0.x.max(self.v1.x).max(self.v2.x),
+            self.v0.y.max(self.v1.y).max(self.v2.y),
+            self.v0.z.max(self.v1.z).max(self.v2.z),
         );
         Aabb::new(min, max)
     }
+
+    fn kind(&self) -> &'static str {
+        "Triangle"
+    }
+
+    fn surface_area(&self) -> f64 {
+        0.5 * (self.v1 - self.v0).cross(self.v2 - self.v0).length()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(self.material.as_ref())
+    }
 }
 
 // ─── Axis-Aligned Quad (Rectangle) ─────────────────────────────────────────
@@ -496,7 +1171,7 @@ impl Quad {
 impl Hittable for Quad {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         let denom = self.normal.dot(ray.direction);
-        if denom.abs() < 1e-8 {
+        if runs_parallel(denom, ray.direction) {
             return None;
         }
 
@@ -520,13 +1195,13 @@ impl Hittable for Quad {
             t,
             front_face: true,
             material: self.material.as_ref(),
+            object: None,
         };
         rec.set_face_normal(ray, self.normal);
         Some(rec)
     }
 
     fn bounding_box(&self) -> Aabb {
-        let eps = Vec3::new(1e-4, 1e-4, 1e-4);
         let p0 = self.origin;
         let p1 = self.origin + self.edge_u;
         let p2 = self.origin + self.edge_v;
@@ -541,7 +1216,39 @@ impl Hittable for Quad {
             p0.y.max(p1.y).max(p2.y).max(p3.y),
             p0.z.max(p1.z).max(p2.z).max(p3.z),
         );
-        Aabb::new(min - eps, max + eps)
+        Aabb::new(min, max)
+    }
+
+    /// Area sampling converted to solid angle: pdf = d² / (|cos θ| · A).
+    fn pdf_value(&self, origin: Point3, direction: Vec3, t_min: f64) -> f64 {
+        let Some(rec) = self.hit(&Ray::new(origin, direction), t_min, f64::INFINITY) else {
+            return 0.0;
+        };
+        let area = self.edge_u.cross(self.edge_v).length();
+        let dist_sq = rec.t * rec.t * direction.length_squared();
+        let cosine = (direction.dot(self.normal) / direction.length()).abs();
+        dist_sq / (cosine * area)
+    }
+
+    fn random_toward(&self, origin: Point3, rng: &mut dyn rand::RngCore) -> Vec3 {
+        let p = self.origin + self.edge_u * rng.gen::<f64>() + self.edge_v * rng.gen::<f64>();
+        p - origin
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn kind(&self) -> &'static str {
+        "Quad"
+    }
+
+    fn surface_area(&self) -> f64 {
+        self.edge_u.cross(self.edge_v).length()
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(self.material.as_ref())
     }
 }
 
@@ -574,7 +1281,7 @@ impl Disk {
 impl Hittable for Disk {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         let denom = ray.direction.dot(self.normal);
-        if denom.abs() < 1e-8 {
+        if runs_parallel(denom, ray.direction) {
             return None;
         }
         let t = (self.center - ray.origin).dot(self.normal) / denom;
@@ -592,22 +1299,61 @@ impl Hittable for Disk {
             t,
             front_face: true,
             material: self.material.as_ref(),
+            object: None,
         };
         rec.set_face_normal(ray, self.normal);
         Some(rec)
     }
 
+    /// The disk's extent along each axis is r·√(1 − n²) for that axis's
+    /// normal component, so a disk facing an axis is flat along it.
     fn bounding_box(&self) -> Aabb {
-        let r = Vec3::new(self.radius, self.radius, self.radius);
+        let n = self.normal;
+        let extent = |c: f64| self.radius * (1.0 - c * c).max(0.0).sqrt();
+        let r = Vec3::new(extent(n.x), extent(n.y), extent(n.z));
         Aabb::new(self.center - r, self.center + r)
     }
+
+    /// Area sampling converted to solid angle: pdf = d² / (|cos θ| · πr²).
+    fn pdf_value(&self, origin: Point3, direction: Vec3, t_min: f64) -> f64 {
+        let Some(rec) = self.hit(&Ray::new(origin, direction), t_min, f64::INFINITY) else {
+            return 0.0;
+        };
+        let area = std::f64::consts::PI * self.radius * self.radius;
+        let dist_sq = rec.t * rec.t * direction.length_squared();
+        let cosine = (direction.dot(self.normal) / direction.length()).abs();
+        dist_sq / (cosine * area)
+    }
+
+    fn random_toward(&self, origin: Point3, rng: &mut dyn rand::RngCore) -> Vec3 {
+        let d = Vec3::random_in_unit_disk(rng) * self.radius;
+        let p = self.center + Onb::from_w(self.normal).local(d.x, d.y, 0.0);
+        p - origin
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn kind(&self) -> &'static str {
+        "Disk"
+    }
+
+    fn surface_area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        Some(self.material.as_ref())
+    }
 }
 
 // ─── Bounding Volume Hierarchy ──────────────────────────────────────────────
 
 pub enum BvhNode {
+    /// Up to `max_leaf_size` primitives, tested one after another.
     Leaf {
-        object: Box<dyn Hittable>,
+        objects: Vec<Box<dyn Hittable>>,
         bbox: Aabb,
     },
     Interior {
@@ -618,38 +1364,70 @@ pub enum BvhNode {
 }
 
 impl BvhNode {
-    pub fn build(mut objects: Vec<Box<dyn Hittable>>) -> Self {
+    /// Builds the hierarchy, splitting until a node holds at most
+    /// `max_leaf_size` primitives. One per leaf minimises intersection
+    /// tests; a few per leaf trades some of them for a shallower tree with
+    /// fewer box tests.
+    pub fn build(objects: Vec<Box<dyn Hittable>>, max_leaf_size: usize) -> Self {
+        if objects.is_empty() {
+            panic!("BVH: empty object list");
+        }
+        let root = objects
+            .iter()
+            .map(|o| o.bounding_box())
+            .reduce(|a, b| Aabb::surrounding(&a, &b))
+            .unwrap();
+        let min_thickness = FLAT_BOX_PAD * extent_scale(&root);
+        BvhNode::build_node(objects, max_leaf_size, min_thickness)
+    }
+
+    /// Builds one subtree, padding its leaf boxes to `min_thickness`.
+    fn build_node(
+        mut objects: Vec<Box<dyn Hittable>>,
+        max_leaf_size: usize,
+        min_thickness: f64,
+    ) -> Self {
         let len = objects.len();
-        match len {
-            0 => panic!("BVH: empty object list"),
-            1 => {
-                let obj = objects.pop().unwrap();
-                let bbox = obj.bounding_box();
-                BvhNode::Leaf { object: obj, bbox }
-            }
-            _ => {
-                let enclosing = objects
-                    .iter()
-                    .map(|o| o.bounding_box())
-                    .reduce(|a, b| Aabb::surrounding(&a, &b))
-                    .unwrap();
-                let axis = enclosing.longest_axis();
-
-                objects.sort_by(|a, b| {
-                    let ac = a.bounding_box().min[axis] + a.bounding_box().max[axis];
-                    let bc = b.bounding_box().min[axis] + b.bounding_box().max[axis];
-                    ac.partial_cmp(&bc).unwrap_or(Ordering::Equal)
-                });
-
-                let mid = len / 2;
-                let right_objs = objects.split_off(mid);
-                let left = Box::new(BvhNode::build(objects));
-                let right = Box::new(BvhNode::build(right_objs));
-                let bbox =
-                    Aabb::surrounding(&left.bounding_box_inner(), &right.bounding_box_inner());
-                BvhNode::Interior { left, right, bbox }
-            }
+        if len <= max_leaf_size.max(1) {
+            let bbox = objects
+                .iter()
+                .map(|o| o.bounding_box())
+                .reduce(|a, b| Aabb::surrounding(&a, &b))
+                .unwrap()
+                .pad(min_thickness);
+            return BvhNode::Leaf { objects, bbox };
         }
+
+        // Split along the longest axis of the *centroid* bounds rather than
+        // the object extents: one huge primitive (a ground sphere) would
+        // otherwise dictate the axis even when all the small objects are
+        // spread along a different one.
+        let centroid_bounds = objects
+            .iter()
+            .map(|o| {
+                let c = o.bounding_box().centroid();
+                Aabb::new(c, c)
+            })
+            .reduce(|a, b| Aabb::surrounding(&a, &b))
+            .unwrap();
+        let axis = centroid_bounds.longest_axis();
+
+        objects.sort_by(|a, b| {
+            let ac = a.bounding_box().centroid()[axis];
+            let bc = b.bounding_box().centroid()[axis];
+            ac.partial_cmp(&bc).unwrap_or(Ordering::Equal)
+        });
+
+        let mid = len / 2;
+        let right_objs = objects.split_off(mid);
+        let left = Box::new(BvhNode::build_node(objects, max_leaf_size, min_thickness));
+        let right = Box::new(BvhNode::build_node(
+            right_objs,
+            max_leaf_size,
+            min_thickness,
+        ));
+        let bbox = Aabb::surrounding(&left.bounding_box_inner(), &right.bounding_box_inner());
+        BvhNode::Interior { left, right, bbox }
     }
 
     fn bounding_box_inner(&self) -> Aabb {
@@ -659,10 +1437,10 @@ impl BvhNode {
         }
     }
 
-    /// Returns the total number of leaf (primitive) nodes in the BVH.
+    /// Returns the total number of primitives stored in the BVH's leaves.
     pub fn leaf_count(&self) -> usize {
         match self {
-            BvhNode::Leaf { .. } => 1,
+            BvhNode::Leaf { objects, .. } => objects.len(),
             BvhNode::Interior { left, right, .. } => left.leaf_count() + right.leaf_count(),
         }
     }
@@ -679,11 +1457,20 @@ impl BvhNode {
 impl Hittable for BvhNode {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         match self {
-            BvhNode::Leaf { object, bbox } => {
+            BvhNode::Leaf { objects, bbox } => {
                 if !bbox.hit(ray, t_min, t_max) {
                     return None;
                 }
-                object.hit(ray, t_min, t_max)
+                // Closest hit: each test only looks nearer than the last.
+                let mut closest = None;
+                let mut far = t_max;
+                for object in objects {
+                    if let Some(rec) = object.hit(ray, t_min, far) {
+                        far = rec.t;
+                        closest = Some(rec);
+                    }
+                }
+                closest
             }
             BvhNode::Interior {
                 left, right, bbox, ..
@@ -703,3 +1490,55 @@ impl Hittable for BvhNode {
         self.bounding_box_inner()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extent_scale_follows_the_bounding_box_diagonal() {
+        let cube = |side: f64| Aabb::new(Point3::zero(), Point3::new(side, side, side));
+        assert!((extent_scale(&cube(10.0)) - 1.0).abs() < 1e-12);
+        assert!((extent_scale(&cube(10_000.0)) - 1000.0).abs() < 1e-9);
+        assert_eq!(
+            extent_scale(&Aabb::new(Point3::zero(), Point3::zero())),
+            1.0
+        );
+    }
+
+    #[test]
+    fn fresnel_reflectance_matches_analytic_limits() {
+        let ior: f64 = 1.5;
+        let normal_incidence = ((ior - 1.0) / (ior + 1.0)).powi(2);
+        for eta_ratio in [1.0 / ior, ior] {
+            let r = Dielectric::fresnel_reflectance(1.0, eta_ratio);
+            assert!((r - normal_incidence).abs() < 1e-12, "normal: {r}");
+        }
+
+        let grazing = Dielectric::fresnel_reflectance(0.0, 1.0 / ior);
+        assert!((grazing - 1.0).abs() < 1e-12, "grazing: {grazing}");
+
+        // From inside glass, past the critical angle asin(1 / 1.5) ≈ 41.8°.
+        assert_eq!(Dielectric::fresnel_reflectance(0.5, ior), 1.0);
+        assert!(Dielectric::fresnel_reflectance(0.8, ior) < 1.0);
+    }
+
+    #[test]
+    fn flat_primitive_hits_do_not_depend_on_direction_length() {
+        let quad = Quad::new(
+            Point3::new(-1.0, -1.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+        );
+        let toward = Vec3::new(0.0, 0.0, -1.0);
+        for length in [1e-10, 1.0, 1e6] {
+            let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), toward * length);
+            let hit = quad.hit(&ray, 0.0, f64::INFINITY).expect("quad is hit");
+            assert!((hit.t * length - 1.0).abs() < 1e-9);
+        }
+        // Grazing rays are parallel whatever their length.
+        let grazing = Ray::new(Point3::new(0.0, 0.0, 1.0), Vec3::new(1e6, 0.0, -1e-10));
+        assert!(quad.hit(&grazing, 0.0, f64::INFINITY).is_none());
+    }
+}